@@ -0,0 +1,174 @@
+use anyhow::{bail, Result};
+
+pub const USAGE: &str =
+    "usage: shell [-eilsx] [--login] [--norc] [-c command [name [arg ...]] | script [arg ...]]";
+
+/// Origine des commandes exécutées par le shell
+#[derive(Debug, PartialEq, Eq)]
+pub enum Source {
+    /// Entrée standard (terminal, pipe...)
+    Stdin,
+    /// Chaîne passée avec `-c`
+    Command(String),
+    /// Fichier de script
+    Script(String),
+}
+
+/// Arguments de la ligne de commande du shell
+#[derive(Debug)]
+pub struct Arguments {
+    pub source: Source,
+    /// Valeur de `$0` : le script, le nom donné après `-c command`, ou le
+    /// nom sous lequel le shell a été lancé
+    pub name: String,
+    /// Paramètres positionnels (`$1`, `$2`...)
+    pub positional: Vec<String>,
+    /// `-i` : mode interactif forcé, même hors d'un terminal
+    pub interactive: bool,
+    /// `-e` : quitter dès qu'une commande échoue
+    pub errexit: bool,
+    /// `-x` : afficher chaque commande avant de l'exécuter
+    pub xtrace: bool,
+    /// `-l`, `--login` ou nom de programme commençant par `-` : shell de
+    /// connexion, qui lit `/etc/profile` et `~/.profile`
+    pub login: bool,
+    /// `--norc` : ne pas lire `~/.shellrc` ni le fichier désigné par `$ENV`
+    pub norc: bool,
+}
+
+impl Arguments {
+    /// Analyse `args`, nom du programme compris.
+    ///
+    /// Les options précèdent les opérandes : avec `-c`, le premier opérande
+    /// est la commande, le suivant `$0` et les autres les paramètres
+    /// positionnels ; avec `-s`, tous sont des paramètres positionnels ;
+    /// sinon le premier est le script à exécuter.
+    pub fn parse(args: impl IntoIterator<Item = String>) -> Result<Self> {
+        let mut args = args.into_iter().peekable();
+        let program = args.next().unwrap_or_else(|| "shell".to_string());
+
+        let mut command = false;
+        let mut stdin = false;
+        let mut arguments = Self {
+            source: Source::Stdin,
+            positional: Vec::new(),
+            interactive: false,
+            errexit: false,
+            xtrace: false,
+            login: program.starts_with('-'),
+            norc: false,
+            name: program,
+        };
+
+        while let Some(arg) = args.next_if(|arg| arg.starts_with('-') && arg.len() > 1) {
+            match arg.as_str() {
+                "--" => break,
+                "--login" => arguments.login = true,
+                "--norc" => arguments.norc = true,
+                long if long.starts_with("--") => bail!("{}: invalid option", long),
+                flags => {
+                    for flag in flags.chars().skip(1) {
+                        match flag {
+                            'c' => command = true,
+                            's' => stdin = true,
+                            'i' => arguments.interactive = true,
+                            'e' => arguments.errexit = true,
+                            'x' => arguments.xtrace = true,
+                            'l' => arguments.login = true,
+                            flag => bail!("-{}: invalid option", flag),
+                        }
+                    }
+                }
+            }
+        }
+
+        // `-` seul termine aussi les options
+        args.next_if(|arg| arg == "-");
+
+        if command {
+            let Some(text) = args.next() else {
+                bail!("-c: option requires an argument");
+            };
+            arguments.source = Source::Command(text);
+            if let Some(name) = args.next() {
+                arguments.name = name;
+            }
+        } else if !stdin {
+            if let Some(script) = args.next() {
+                arguments.name = script.clone();
+                arguments.source = Source::Script(script);
+            }
+        }
+
+        arguments.positional = args.collect();
+        Ok(arguments)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<Arguments> {
+        Arguments::parse(args.iter().map(|arg| arg.to_string()))
+    }
+
+    #[test]
+    fn stdin_by_default() {
+        let arguments = parse(&["shell"]).unwrap();
+        assert_eq!(arguments.source, Source::Stdin);
+        assert_eq!(arguments.name, "shell");
+        assert!(arguments.positional.is_empty());
+        assert!(!arguments.login);
+
+        let arguments = parse(&["shell", "-s", "a", "b"]).unwrap();
+        assert_eq!(arguments.source, Source::Stdin);
+        assert_eq!(arguments.positional, ["a", "b"]);
+    }
+
+    #[test]
+    fn command_string() {
+        let arguments = parse(&["shell", "-c", "echo $1", "name", "a", "b"]).unwrap();
+        assert_eq!(arguments.source, Source::Command("echo $1".to_string()));
+        assert_eq!(arguments.name, "name");
+        assert_eq!(arguments.positional, ["a", "b"]);
+
+        let arguments = parse(&["shell", "-c", "true"]).unwrap();
+        assert_eq!(arguments.name, "shell");
+        assert!(arguments.positional.is_empty());
+
+        // Les options peuvent suivre `-c` dans le même mot ou le suivant
+        let arguments = parse(&["shell", "-ce", "-x", "true"]).unwrap();
+        assert_eq!(arguments.source, Source::Command("true".to_string()));
+        assert!(arguments.errexit && arguments.xtrace);
+
+        assert_eq!(parse(&["shell", "-c"]).unwrap_err().to_string(), "-c: option requires an argument");
+    }
+
+    #[test]
+    fn script() {
+        let arguments = parse(&["shell", "run.sh", "-x", "b"]).unwrap();
+        assert_eq!(arguments.source, Source::Script("run.sh".to_string()));
+        assert_eq!(arguments.name, "run.sh");
+        assert_eq!(arguments.positional, ["-x", "b"]);
+        assert!(!arguments.xtrace);
+
+        // `--` et `-` terminent les options
+        let arguments = parse(&["shell", "--", "-script"]).unwrap();
+        assert_eq!(arguments.source, Source::Script("-script".to_string()));
+        let arguments = parse(&["shell", "-", "script"]).unwrap();
+        assert_eq!(arguments.source, Source::Script("script".to_string()));
+    }
+
+    #[test]
+    fn flags() {
+        let arguments = parse(&["shell", "-ie", "--norc"]).unwrap();
+        assert!(arguments.interactive && arguments.errexit && arguments.norc);
+        assert!(parse(&["shell", "--login"]).unwrap().login);
+        assert!(parse(&["shell", "-l"]).unwrap().login);
+        assert!(parse(&["-shell"]).unwrap().login);
+
+        assert_eq!(parse(&["shell", "-q"]).unwrap_err().to_string(), "-q: invalid option");
+        assert_eq!(parse(&["shell", "--nope"]).unwrap_err().to_string(), "--nope: invalid option");
+    }
+}
@@ -1,14 +1,15 @@
-use crate::commands::CommandRegistry;
+use crate::shell::Shell;
 use anyhow::Result;
 use crate::execution::RedirectionManager;
 
 pub trait CommandHandler {
     fn name(&self) -> &'static str;
+    /// Exécute la commande et retourne son code de retour
     fn execute(
         &self,
         args: &[String],
-        registry: &CommandRegistry,
+        shell: &mut Shell,
         redirections: &mut RedirectionManager,
-    ) -> Result<bool>;
+    ) -> Result<i32>;
     fn help(&self) -> &'static str;
 }
@@ -0,0 +1,63 @@
+use crate::command::CommandHandler;
+use crate::execution::RedirectionManager;
+use crate::shell::Shell;
+use crate::utils::error::strerror;
+use anyhow::Result;
+
+pub struct BgHandler;
+
+impl CommandHandler for BgHandler {
+    fn name(&self) -> &'static str {
+        "bg"
+    }
+
+    fn execute(&self,
+               args: &[String],
+               shell: &mut Shell,
+               redirections: &mut RedirectionManager,
+    ) -> Result<i32> {
+        if !shell.has_job_control() {
+            writeln!(redirections.stderr(), "bg: no job control")?;
+            return Ok(1);
+        }
+
+        // Sans argument, le job courant
+        let specs: Vec<Option<&str>> = if args.is_empty() {
+            vec![None]
+        } else {
+            args.iter().map(|arg| Some(arg.as_str())).collect()
+        };
+
+        let mut status = 0;
+        for spec in specs {
+            let id = match shell.jobs().resolve(spec) {
+                Ok(id) => id,
+                Err(err) => {
+                    writeln!(redirections.stderr(), "bg: {}", err)?;
+                    status = 1;
+                    continue;
+                }
+            };
+
+            let jobs = shell.jobs_mut();
+            let Some(job) = jobs.get_mut(id) else {
+                continue;
+            };
+            if let Err(err) = job.resume() {
+                writeln!(redirections.stderr(), "bg: {}", strerror(&err))?;
+                status = 1;
+                continue;
+            }
+
+            let command = job.command().to_string();
+            jobs.touch(id);
+            writeln!(redirections.stdout(), "[{}]+ {} &", id, command)?;
+        }
+
+        Ok(status)
+    }
+
+    fn help(&self) -> &'static str {
+        "bg [jobspec ...] - Resume jobs in the background"
+    }
+}
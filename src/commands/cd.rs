@@ -1,6 +1,6 @@
 use std::path::PathBuf;
 use crate::command::CommandHandler;
-use crate::commands::CommandRegistry;
+use crate::shell::Shell;
 use anyhow::Result;
 use crate::execution::RedirectionManager;
 
@@ -13,42 +13,43 @@ impl CommandHandler for CdHandler {
 
     fn execute(&self,
                args: &[String],
-               _registry: &CommandRegistry,
+               shell: &mut Shell,
                redirections: &mut RedirectionManager,
-    ) -> Result<bool> {
+    ) -> Result<i32> {
         let target_dir = if args.is_empty() {
-            std::env::var("HOME").unwrap_or_else(|_| "/".to_string())
+            shell.variables().get("HOME").unwrap_or_else(|| "/".to_string())
         } else if args.len() == 1 {
             args[0].to_string()
         } else {
             writeln!(redirections.stderr(), "cd: too many arguments")?;
-            return Ok(true);
+            return Ok(1);
         };
 
-        let new_path = if target_dir.starts_with('/') || target_dir.starts_with('~') {
-            if target_dir.starts_with('~') {
-                let home = std::env::var("HOME")
-                    .unwrap_or_else(|_| "/".to_string());
-                PathBuf::from(target_dir.replacen('~', &home, 1))
-            } else {
-                PathBuf::from(&target_dir)
-            }
+        // Le `~` éventuel a déjà été remplacé lors de l'expansion des mots
+        let old_path = std::env::current_dir()?;
+        let new_path = if target_dir.starts_with('/') {
+            PathBuf::from(&target_dir)
         } else {
-            std::env::current_dir()?.join(&target_dir)
+            old_path.join(&target_dir)
         };
 
         if !new_path.exists() {
             writeln!(redirections.stderr(), "cd: {}: No such file or directory", target_dir)?;
-            return Ok(true);
+            return Ok(1);
         }
 
         if !new_path.is_dir() {
             writeln!(redirections.stderr(), "cd: {}: Not a directory", target_dir)?;
-            return Ok(true);
+            return Ok(1);
         }
 
         std::env::set_current_dir(new_path)?;
-        Ok(true)
+
+        // `~-` et `~+` s'appuient sur ces variables
+        let variables = shell.variables_mut();
+        variables.set("OLDPWD", &old_path.to_string_lossy());
+        variables.set("PWD", &std::env::current_dir()?.to_string_lossy());
+        Ok(0)
     }
 
     fn help(&self) -> &'static str {
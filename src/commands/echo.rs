@@ -1,5 +1,5 @@
 use crate::command::CommandHandler;
-use crate::commands::CommandRegistry;
+use crate::shell::Shell;
 use anyhow::Result;
 use crate::execution::RedirectionManager;
 
@@ -12,11 +12,11 @@ impl CommandHandler for EchoHandler {
 
     fn execute(&self,
                args: &[String],
-               _registry: &CommandRegistry,
+               _shell: &mut Shell,
                redirections: &mut RedirectionManager,
-    ) -> Result<bool> {
+    ) -> Result<i32> {
         writeln!(redirections.stdout(), "{}", args.join(" "))?;
-        Ok(true)
+        Ok(0)
     }
 
     fn help(&self) -> &'static str {
@@ -1,5 +1,5 @@
 use crate::command::CommandHandler;
-use crate::commands::CommandRegistry;
+use crate::shell::Shell;
 use anyhow::Result;
 use crate::execution::RedirectionManager;
 
@@ -12,10 +12,21 @@ impl CommandHandler for ExitHandler {
 
     fn execute(&self,
                args: &[String],
-               _registry: &CommandRegistry,
-               _redirections: &mut RedirectionManager,
-    ) -> Result<bool> {
-        let code = args.get(0).and_then(|s| s.parse().ok()).unwrap_or(0);
+               shell: &mut Shell,
+               redirections: &mut RedirectionManager,
+    ) -> Result<i32> {
+        // Sans argument, on sort avec le code de la dernière commande ; un
+        // code hors de 0..=255 est ramené modulo 256 (`exit -1` vaut 255)
+        let code = match args.first() {
+            Some(arg) => match arg.parse::<i64>() {
+                Ok(code) => code.rem_euclid(256) as i32,
+                Err(_) => {
+                    writeln!(redirections.stderr(), "exit: {}: numeric argument required", arg)?;
+                    2
+                }
+            },
+            None => shell.last_status(),
+        };
         std::process::exit(code);
     }
 
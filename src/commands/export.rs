@@ -0,0 +1,48 @@
+use crate::command::CommandHandler;
+use crate::execution::RedirectionManager;
+use crate::parser::is_name;
+use crate::shell::Shell;
+use anyhow::Result;
+
+pub struct ExportHandler;
+
+impl CommandHandler for ExportHandler {
+    fn name(&self) -> &'static str {
+        "export"
+    }
+
+    fn execute(&self,
+               args: &[String],
+               shell: &mut Shell,
+               redirections: &mut RedirectionManager,
+    ) -> Result<i32> {
+        if args.is_empty() {
+            for (name, value) in shell.variables().exported() {
+                writeln!(redirections.stdout(), "export {}=\"{}\"", name, value)?;
+            }
+            return Ok(0);
+        }
+
+        let mut status = 0;
+        for arg in args {
+            let (name, value) = match arg.split_once('=') {
+                Some((name, value)) => (name, Some(value)),
+                None => (arg.as_str(), None),
+            };
+
+            if !is_name(name) {
+                writeln!(redirections.stderr(), "export: `{}': not a valid identifier", arg)?;
+                status = 1;
+                continue;
+            }
+
+            shell.variables_mut().export(name, value);
+        }
+
+        Ok(status)
+    }
+
+    fn help(&self) -> &'static str {
+        "export [name[=value] ...] - Export variables to the environment of commands"
+    }
+}
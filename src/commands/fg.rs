@@ -0,0 +1,42 @@
+use crate::command::CommandHandler;
+use crate::execution::RedirectionManager;
+use crate::shell::Shell;
+use anyhow::Result;
+
+pub struct FgHandler;
+
+impl CommandHandler for FgHandler {
+    fn name(&self) -> &'static str {
+        "fg"
+    }
+
+    fn execute(&self,
+               args: &[String],
+               shell: &mut Shell,
+               redirections: &mut RedirectionManager,
+    ) -> Result<i32> {
+        if !shell.has_job_control() {
+            writeln!(redirections.stderr(), "fg: no job control")?;
+            return Ok(1);
+        }
+
+        let id = match shell.jobs().resolve(args.first().map(String::as_str)) {
+            Ok(id) => id,
+            Err(err) => {
+                writeln!(redirections.stderr(), "fg: {}", err)?;
+                return Ok(1);
+            }
+        };
+
+        if let Some(job) = shell.jobs().get(id) {
+            writeln!(redirections.stdout(), "{}", job.command())?;
+        }
+        redirections.stdout().flush()?;
+
+        shell.foreground_job(id)
+    }
+
+    fn help(&self) -> &'static str {
+        "fg [jobspec] - Resume a job in the foreground"
+    }
+}
@@ -1,5 +1,5 @@
 use crate::command::CommandHandler;
-use crate::commands::CommandRegistry;
+use crate::shell::Shell;
 use anyhow::Result;
 use crate::execution::RedirectionManager;
 
@@ -12,14 +12,17 @@ impl CommandHandler for HelpHandler {
 
     fn execute(&self,
                args: &[String],
-               registry: &CommandRegistry,
+               shell: &mut Shell,
                redirections: &mut RedirectionManager,
-    ) -> Result<bool> {
-        if let Some(cmd_name) = args.get(0) {
+    ) -> Result<i32> {
+        let registry = shell.registry();
+
+        if let Some(cmd_name) = args.first() {
             if let Some(handler) = registry.get(cmd_name) {
                 writeln!(redirections.stdout(), "{}", handler.help())?;
             } else {
                 writeln!(redirections.stderr(), "Unknown command: {}", cmd_name)?;
+                return Ok(1);
             }
         } else {
             writeln!(redirections.stdout(), "Available commands:")?;
@@ -29,7 +32,7 @@ impl CommandHandler for HelpHandler {
                 }
             }
         }
-        Ok(true)
+        Ok(0)
     }
 
     fn help(&self) -> &'static str {
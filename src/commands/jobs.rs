@@ -0,0 +1,74 @@
+use crate::command::CommandHandler;
+use crate::execution::RedirectionManager;
+use crate::shell::Shell;
+use anyhow::Result;
+
+pub struct JobsHandler;
+
+impl CommandHandler for JobsHandler {
+    fn name(&self) -> &'static str {
+        "jobs"
+    }
+
+    fn execute(&self,
+               args: &[String],
+               shell: &mut Shell,
+               redirections: &mut RedirectionManager,
+    ) -> Result<i32> {
+        let mut with_pid = false;
+        let mut pids_only = false;
+        let mut specs = Vec::new();
+
+        for arg in args {
+            match arg.as_str() {
+                "-l" => with_pid = true,
+                "-p" => pids_only = true,
+                flag if flag.starts_with('-') && flag.len() > 1 => {
+                    writeln!(redirections.stderr(), "jobs: {}: invalid option", flag)?;
+                    writeln!(redirections.stderr(), "jobs: usage: jobs [-lp] [jobspec ...]")?;
+                    return Ok(2);
+                }
+                spec => specs.push(spec),
+            }
+        }
+
+        shell.jobs_mut().reap();
+
+        let mut status = 0;
+        let ids = if specs.is_empty() {
+            shell.jobs().ids()
+        } else {
+            let mut ids = Vec::new();
+            for spec in specs {
+                match shell.jobs().resolve(Some(spec)) {
+                    Ok(id) => ids.push(id),
+                    Err(err) => {
+                        writeln!(redirections.stderr(), "jobs: {}", err)?;
+                        status = 1;
+                    }
+                }
+            }
+            ids
+        };
+
+        let jobs = shell.jobs_mut();
+        for id in ids {
+            if pids_only {
+                if let Some(pgid) = jobs.get(id).and_then(|job| job.pgid()) {
+                    writeln!(redirections.stdout(), "{}", pgid)?;
+                }
+                continue;
+            }
+            writeln!(redirections.stdout(), "{}", jobs.format(id, with_pid))?;
+            jobs.mark_reported(id);
+        }
+
+        // Les jobs terminés ne sont listés qu'une fois
+        jobs.remove_finished();
+        Ok(status)
+    }
+
+    fn help(&self) -> &'static str {
+        "jobs [-lp] [jobspec ...] - List background and stopped jobs"
+    }
+}
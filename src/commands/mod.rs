@@ -1,9 +1,18 @@
+mod bg;
 mod cd;
 mod echo;
 mod exit;
+mod export;
+mod fg;
 mod help;
+mod jobs;
 mod pwd;
+mod read;
+mod set;
+mod shopt;
+mod source;
 mod type_cmd;
+mod unset;
 
 use crate::command::CommandHandler;
 use std::collections::HashMap;
@@ -24,6 +33,16 @@ impl CommandRegistry {
         registry.register(Box::new(cd::CdHandler));
         registry.register(Box::new(type_cmd::TypeHandler));
         registry.register(Box::new(help::HelpHandler));
+        registry.register(Box::new(export::ExportHandler));
+        registry.register(Box::new(unset::UnsetHandler));
+        registry.register(Box::new(shopt::ShoptHandler));
+        registry.register(Box::new(jobs::JobsHandler));
+        registry.register(Box::new(fg::FgHandler));
+        registry.register(Box::new(bg::BgHandler));
+        registry.register(Box::new(read::ReadHandler));
+        registry.register(Box::new(set::SetHandler));
+        registry.register(Box::new(source::SourceHandler { name: "source" }));
+        registry.register(Box::new(source::SourceHandler { name: "." }));
 
         registry
     }
@@ -32,8 +51,8 @@ impl CommandRegistry {
         self.handlers.insert(handler.name(), handler);
     }
 
-    pub fn get(&self, name: &str) -> Option<&Box<dyn CommandHandler>> {
-        self.handlers.get(name)
+    pub fn get(&self, name: &str) -> Option<&dyn CommandHandler> {
+        self.handlers.get(name).map(|handler| handler.as_ref())
     }
 
     pub fn list_commands(&self) -> Vec<&'static str> {
@@ -0,0 +1,117 @@
+use crate::command::CommandHandler;
+use crate::execution::RedirectionManager;
+use crate::expansion::DEFAULT_IFS;
+use crate::parser::is_name;
+use crate::shell::Shell;
+use anyhow::Result;
+use std::io::{self, Read};
+
+pub struct ReadHandler;
+
+impl CommandHandler for ReadHandler {
+    fn name(&self) -> &'static str {
+        "read"
+    }
+
+    fn execute(&self,
+               args: &[String],
+               shell: &mut Shell,
+               redirections: &mut RedirectionManager,
+    ) -> Result<i32> {
+        let mut raw = false;
+        let mut names = Vec::new();
+        for arg in args {
+            match arg.as_str() {
+                "-r" if names.is_empty() => raw = true,
+                name if is_name(name) => names.push(name),
+                _ => {
+                    writeln!(redirections.stderr(), "read: `{}': not a valid identifier", arg)?;
+                    return Ok(1);
+                }
+            }
+        }
+
+        let (line, complete) = match read_line(redirections.stdin(), raw) {
+            Ok(result) => result,
+            Err(err) => {
+                writeln!(redirections.stderr(), "read: read error: {}", err)?;
+                return Ok(1);
+            }
+        };
+
+        if names.is_empty() {
+            shell.variables_mut().set("REPLY", &line);
+        } else {
+            let ifs = shell.variables().get("IFS").unwrap_or_else(|| DEFAULT_IFS.to_string());
+            for (name, value) in names.iter().zip(split_line(&line, &ifs, names.len())) {
+                shell.variables_mut().set(name, value);
+            }
+        }
+
+        // Comme bash : la fin de fichier avant un saut de ligne est un échec,
+        // même si une ligne partielle a été affectée
+        Ok(if complete { 0 } else { 1 })
+    }
+
+    fn help(&self) -> &'static str {
+        "read [-r] [name ...] - Read a line from standard input and split it into variables"
+    }
+}
+
+/// Lit une ligne octet par octet, pour ne rien consommer au-delà du saut de
+/// ligne. Sans `-r`, `\` protège le caractère suivant et une barre oblique
+/// en fin de ligne la prolonge sur la suivante.
+///
+/// Retourne la ligne et `false` si la fin de fichier a été atteinte avant
+/// le saut de ligne.
+fn read_line(input: &mut dyn Read, raw: bool) -> io::Result<(String, bool)> {
+    let mut line = Vec::new();
+    let mut escaped = false;
+    let mut byte = [0u8];
+
+    let complete = loop {
+        match input.read(&mut byte) {
+            Ok(0) => break false,
+            Ok(_) => {}
+            Err(err) if err.kind() == io::ErrorKind::Interrupted => continue,
+            Err(err) => return Err(err),
+        }
+
+        match byte[0] {
+            b'\n' if escaped => escaped = false,
+            b'\n' => break true,
+            b'\\' if !raw && !escaped => escaped = true,
+            other => {
+                line.push(other);
+                escaped = false;
+            }
+        }
+    };
+
+    Ok((String::from_utf8_lossy(&line).into_owned(), complete))
+}
+
+/// Découpe `line` selon `ifs` en au plus `count` champs, le dernier
+/// recevant le reste de la ligne
+fn split_line<'a>(line: &'a str, ifs: &str, count: usize) -> Vec<&'a str> {
+    let is_blank = |c: char| ifs.contains(c) && c.is_ascii_whitespace();
+    let is_delimiter = |c: char| ifs.contains(c);
+
+    let mut fields = Vec::with_capacity(count);
+    let mut rest = line.trim_matches(is_blank);
+    while fields.len() + 1 < count {
+        let Some(end) = rest.find(is_delimiter) else { break };
+        fields.push(&rest[..end]);
+
+        // Un délimiteur non blanc compte une seule fois, entouré de blancs
+        rest = rest[end..].trim_start_matches(is_blank);
+        if let Some(after) = rest.strip_prefix(|c: char| is_delimiter(c) && !is_blank(c)) {
+            rest = after.trim_start_matches(is_blank);
+        }
+    }
+    fields.push(rest);
+
+    // Les variables en trop sont vidées
+    fields.resize(count, "");
+    fields
+}
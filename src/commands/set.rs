@@ -0,0 +1,52 @@
+use crate::command::CommandHandler;
+use crate::execution::RedirectionManager;
+use crate::shell::Shell;
+use anyhow::Result;
+
+pub struct SetHandler;
+
+impl CommandHandler for SetHandler {
+    fn name(&self) -> &'static str {
+        "set"
+    }
+
+    fn execute(&self,
+               args: &[String],
+               shell: &mut Shell,
+               redirections: &mut RedirectionManager,
+    ) -> Result<i32> {
+        let mut args = args.iter();
+        let mut positional = None;
+
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--" => {
+                    positional = Some(args.by_ref().cloned().collect());
+                    break;
+                }
+                "-e" => shell.set_errexit(true),
+                "+e" => shell.set_errexit(false),
+                flag if flag.starts_with(['-', '+']) => {
+                    writeln!(redirections.stderr(), "set: {}: invalid option", flag)?;
+                    writeln!(redirections.stderr(), "set: usage: set [-e|+e] [--] [arg ...]")?;
+                    return Ok(2);
+                }
+                // Le premier argument qui n'est pas une option commence les
+                // paramètres positionnels
+                _ => {
+                    positional = Some(std::iter::once(arg).chain(args.by_ref()).cloned().collect());
+                    break;
+                }
+            }
+        }
+
+        if let Some(positional) = positional {
+            shell.set_positional_params(positional);
+        }
+        Ok(0)
+    }
+
+    fn help(&self) -> &'static str {
+        "set [-e|+e] [--] [arg ...] - Set shell options and positional parameters"
+    }
+}
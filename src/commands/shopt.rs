@@ -0,0 +1,78 @@
+use crate::command::CommandHandler;
+use crate::execution::RedirectionManager;
+use crate::options::ShellOptions;
+use crate::shell::Shell;
+use anyhow::Result;
+
+pub struct ShoptHandler;
+
+impl CommandHandler for ShoptHandler {
+    fn name(&self) -> &'static str {
+        "shopt"
+    }
+
+    fn execute(&self,
+               args: &[String],
+               shell: &mut Shell,
+               redirections: &mut RedirectionManager,
+    ) -> Result<i32> {
+        let mut value = None;
+        let mut quiet = false;
+        let mut names = Vec::new();
+
+        for arg in args {
+            match arg.as_str() {
+                "-s" => value = Some(true),
+                "-u" => value = Some(false),
+                "-q" => quiet = true,
+                flag if flag.starts_with('-') => {
+                    writeln!(redirections.stderr(), "shopt: {}: invalid option", flag)?;
+                    writeln!(redirections.stderr(), "shopt: usage: shopt [-squ] [optname ...]")?;
+                    return Ok(2);
+                }
+                name => names.push(name),
+            }
+        }
+
+        if let Some(value) = value {
+            let mut status = 0;
+            for name in names {
+                if let Err(err) = shell.options_mut().set(name, value) {
+                    writeln!(redirections.stderr(), "shopt: {}", err)?;
+                    status = 1;
+                }
+            }
+            return Ok(status);
+        }
+
+        if names.is_empty() {
+            names.extend(ShellOptions::NAMES);
+        }
+
+        // Code de retour 1 si l'une des options demandées est désactivée
+        let mut status = 0;
+        for name in names {
+            match shell.options().get(name) {
+                Some(enabled) => {
+                    if !quiet {
+                        let state = if enabled { "on" } else { "off" };
+                        writeln!(redirections.stdout(), "{:<15}\t{}", name, state)?;
+                    }
+                    if !enabled {
+                        status = 1;
+                    }
+                }
+                None => {
+                    writeln!(redirections.stderr(), "shopt: {}: invalid shell option name", name)?;
+                    status = 1;
+                }
+            }
+        }
+
+        Ok(status)
+    }
+
+    fn help(&self) -> &'static str {
+        "shopt [-squ] [optname ...] - Set, unset or show shell options"
+    }
+}
@@ -0,0 +1,67 @@
+use std::path::PathBuf;
+
+use crate::command::CommandHandler;
+use crate::execution::RedirectionManager;
+use crate::shell::Shell;
+use crate::utils::error::strerror;
+use crate::utils::path::find_file_in_path;
+use anyhow::Result;
+
+/// `source` et son synonyme `.`
+pub struct SourceHandler {
+    pub name: &'static str,
+}
+
+impl CommandHandler for SourceHandler {
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    fn execute(&self,
+               args: &[String],
+               shell: &mut Shell,
+               redirections: &mut RedirectionManager,
+    ) -> Result<i32> {
+        let Some(filename) = args.first() else {
+            writeln!(redirections.stderr(), "{}: filename argument required", self.name)?;
+            writeln!(redirections.stderr(), "{}: usage: {} filename [arguments]", self.name, self.name)?;
+            return Ok(2);
+        };
+
+        // Sans `/`, le fichier est cherché dans PATH puis dans le dossier courant
+        let path = if filename.contains('/') {
+            PathBuf::from(filename)
+        } else {
+            find_file_in_path(filename).unwrap_or_else(|| PathBuf::from(filename))
+        };
+
+        // Les arguments supplémentaires remplacent les paramètres positionnels
+        // le temps de l'exécution du fichier
+        let saved_params = (args.len() > 1).then(|| {
+            let saved = shell.positional_params().to_vec();
+            shell.set_positional_params(args[1..].to_vec());
+            saved
+        });
+
+        let result = shell.source_file(&path);
+
+        if let Some(saved) = saved_params {
+            shell.set_positional_params(saved);
+        }
+
+        match result {
+            Ok(status) => Ok(status),
+            Err(err) => {
+                writeln!(redirections.stderr(), "{}: {}: {}", self.name, filename, strerror(&err))?;
+                Ok(1)
+            }
+        }
+    }
+
+    fn help(&self) -> &'static str {
+        match self.name {
+            "." => ". filename [arguments] - Execute commands from a file in the current shell",
+            _ => "source filename [arguments] - Execute commands from a file in the current shell",
+        }
+    }
+}
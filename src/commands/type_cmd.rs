@@ -1,5 +1,5 @@
 use crate::command::CommandHandler;
-use crate::commands::CommandRegistry;
+use crate::shell::Shell;
 use crate::execution::RedirectionManager;
 use anyhow::Result;
 
@@ -12,29 +12,30 @@ impl CommandHandler for TypeHandler {
 
     fn execute(&self,
                args: &[String],
-               registry: &CommandRegistry,
+               shell: &mut Shell,
                redirections: &mut RedirectionManager,
-    ) -> Result<bool> {
+    ) -> Result<i32> {
         if args.len() != 1 {
             writeln!(redirections.stderr(), "type takes exactly one argument")?;
-            return Ok(true);
+            return Ok(1);
         }
 
         let cmd_name = &args[0];
-        let builtin_commands: Vec<&str> = registry.list_commands();
+        let builtin_commands: Vec<&str> = shell.registry().list_commands();
 
         if builtin_commands.contains(&cmd_name.as_str()) {
             writeln!(redirections.stdout(), "{} is a shell builtin", cmd_name)?;
-            return Ok(true);
+            return Ok(0);
         }
 
         if let Some(path) = crate::utils::path::find_executable_in_path(cmd_name) {
             writeln!(redirections.stdout(), "{} is {}", cmd_name, path.to_str().unwrap())?;
         } else {
             writeln!(redirections.stderr(), "{}: not found", cmd_name)?;
+            return Ok(1);
         }
 
-        Ok(true)
+        Ok(0)
     }
 
     fn help(&self) -> &'static str {
@@ -0,0 +1,27 @@
+use crate::command::CommandHandler;
+use crate::execution::RedirectionManager;
+use crate::shell::Shell;
+use anyhow::Result;
+
+pub struct UnsetHandler;
+
+impl CommandHandler for UnsetHandler {
+    fn name(&self) -> &'static str {
+        "unset"
+    }
+
+    fn execute(&self,
+               args: &[String],
+               shell: &mut Shell,
+               _redirections: &mut RedirectionManager,
+    ) -> Result<i32> {
+        for name in args {
+            shell.variables_mut().unset(name);
+        }
+        Ok(0)
+    }
+
+    fn help(&self) -> &'static str {
+        "unset [name ...] - Remove shell variables"
+    }
+}
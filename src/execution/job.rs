@@ -0,0 +1,426 @@
+use std::io;
+use termios::Termios;
+
+use crate::execution::process::{self, Pid, WaitStatus};
+use crate::execution::signals;
+use crate::utils::error::strerror;
+
+/// État d'un job, déduit de celui de ses processus
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JobState {
+    Running,
+    Stopped,
+    Done,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ProcessState {
+    Running,
+    Stopped,
+    /// Terminé : `WaitStatus::Exited` ou `WaitStatus::Signaled`
+    Done(WaitStatus),
+}
+
+#[derive(Debug)]
+struct JobProcess {
+    /// `None` pour une étape qui n'a pas pu être lancée
+    pid: Option<Pid>,
+    state: ProcessState,
+}
+
+/// Pipeline (ou liste exécutée dans un sous-shell) lancé par le shell : ses
+/// processus partagent un groupe dont le leader est le premier d'entre eux.
+#[derive(Debug)]
+pub struct Job {
+    /// Numéro `%n`, attribué à l'entrée dans la table (0 avant)
+    id: usize,
+    pgid: Option<Pid>,
+    command: String,
+    processes: Vec<JobProcess>,
+    /// Dernier état affiché à l'utilisateur, pour ne notifier que les
+    /// changements
+    reported: JobState,
+    /// Paramètres du terminal laissés par le job quand il a quitté le
+    /// premier plan, rétablis lorsqu'il y revient
+    terminal_modes: Option<Termios>,
+}
+
+impl Job {
+    pub fn new(command: String) -> Self {
+        Self {
+            id: 0,
+            pgid: None,
+            command,
+            processes: Vec::new(),
+            reported: JobState::Running,
+            terminal_modes: None,
+        }
+    }
+
+    /// Ajoute une étape : son PID, ou son code de retour si elle n'a pas pu
+    /// être lancée. Le premier processus lancé devient leader du groupe.
+    pub fn add_process(&mut self, stage: Result<Pid, i32>) {
+        let process = match stage {
+            Ok(pid) => {
+                self.pgid.get_or_insert(pid);
+                JobProcess {
+                    pid: Some(pid),
+                    state: ProcessState::Running,
+                }
+            }
+            Err(status) => JobProcess {
+                pid: None,
+                state: ProcessState::Done(WaitStatus::Exited(status)),
+            },
+        };
+        self.processes.push(process);
+    }
+
+    pub fn id(&self) -> usize {
+        self.id
+    }
+
+    pub fn pgid(&self) -> Option<Pid> {
+        self.pgid
+    }
+
+    pub fn command(&self) -> &str {
+        &self.command
+    }
+
+    pub fn terminal_modes(&self) -> Option<&Termios> {
+        self.terminal_modes.as_ref()
+    }
+
+    pub fn set_terminal_modes(&mut self, modes: Option<Termios>) {
+        self.terminal_modes = modes;
+    }
+
+    pub fn pids(&self) -> impl Iterator<Item = Pid> + '_ {
+        self.processes.iter().filter_map(|process| process.pid)
+    }
+
+    pub fn last_pid(&self) -> Option<Pid> {
+        self.pids().last()
+    }
+
+    pub fn state(&self) -> JobState {
+        let states = self.processes.iter().map(|process| process.state);
+        if states.clone().all(|state| matches!(state, ProcessState::Done(_))) {
+            JobState::Done
+        } else if states.clone().any(|state| state == ProcessState::Stopped) {
+            JobState::Stopped
+        } else {
+            JobState::Running
+        }
+    }
+
+    /// Code de retour du job : celui de sa dernière étape
+    pub fn status(&self) -> i32 {
+        match self.processes.last().map(|process| process.state) {
+            Some(ProcessState::Done(WaitStatus::Exited(code))) => code,
+            Some(ProcessState::Done(WaitStatus::Signaled(signal))) => 128 + signal,
+            Some(ProcessState::Stopped) => 128 + libc::SIGTSTP,
+            _ => 0,
+        }
+    }
+
+    /// Un processus du job a été tué par Ctrl-C (`SIGINT`)
+    pub fn interrupted(&self) -> bool {
+        self.processes
+            .iter()
+            .any(|process| process.state == ProcessState::Done(WaitStatus::Signaled(libc::SIGINT)))
+    }
+
+    /// Enregistre un changement d'état rapporté par `waitpid` ; retourne
+    /// `false` si `pid` n'appartient pas au job
+    fn update(&mut self, pid: Pid, status: WaitStatus) -> bool {
+        let Some(process) = self.processes.iter_mut().find(|process| process.pid == Some(pid)) else {
+            return false;
+        };
+
+        process.state = match status {
+            WaitStatus::Stopped => ProcessState::Stopped,
+            status => ProcessState::Done(status),
+        };
+        true
+    }
+
+    /// Attend que tous les processus du job se terminent, ou que l'un d'eux
+    /// soit suspendu
+    pub fn wait(&mut self) {
+        for index in 0..self.processes.len() {
+            let process = &mut self.processes[index];
+            let (Some(pid), ProcessState::Running) = (process.pid, process.state) else {
+                continue;
+            };
+
+            match process::wait_status(pid, false) {
+                Ok(Some((_, WaitStatus::Stopped))) => {
+                    process.state = ProcessState::Stopped;
+                    return;
+                }
+                Ok(Some((_, status))) => process.state = ProcessState::Done(status),
+                Ok(None) => {}
+                Err(err) => {
+                    eprintln!("wait: {}", strerror(&err));
+                    process.state = ProcessState::Done(WaitStatus::Exited(1));
+                }
+            }
+        }
+    }
+
+    /// Relance les processus suspendus du job (`SIGCONT`)
+    pub fn resume(&mut self) -> io::Result<()> {
+        if let Some(pgid) = self.pgid {
+            signals::signal_group(pgid, libc::SIGCONT)?;
+        }
+        for process in &mut self.processes {
+            if process.state == ProcessState::Stopped {
+                process.state = ProcessState::Running;
+            }
+        }
+        self.reported = JobState::Running;
+        Ok(())
+    }
+
+    /// Libellé de l'état, tel qu'affiché par `jobs`
+    fn describe_state(&self) -> String {
+        match self.state() {
+            JobState::Running => "Running".to_string(),
+            JobState::Stopped => "Stopped".to_string(),
+            JobState::Done => match self.processes.last().map(|process| process.state) {
+                Some(ProcessState::Done(WaitStatus::Signaled(signal))) => signals::describe(signal),
+                _ => match self.status() {
+                    0 => "Done".to_string(),
+                    code => format!("Exit {}", code),
+                },
+            },
+        }
+    }
+}
+
+/// Jobs en arrière-plan ou suspendus du shell
+#[derive(Debug, Default)]
+pub struct JobTable {
+    /// Triés par numéro
+    jobs: Vec<Job>,
+    /// Numéros du moins au plus récemment utilisé : le dernier est le job
+    /// courant (`%+`), l'avant-dernier le précédent (`%-`)
+    recent: Vec<usize>,
+}
+
+impl JobTable {
+    /// Ajoute un job et en fait le job courant ; un job déjà numéroté (repris
+    /// par `fg` puis suspendu de nouveau) garde son numéro
+    pub fn insert(&mut self, mut job: Job) -> usize {
+        if job.id == 0 {
+            job.id = self.jobs.iter().map(Job::id).max().unwrap_or(0) + 1;
+        }
+        let id = job.id;
+
+        let position = self.jobs.partition_point(|other| other.id < id);
+        self.jobs.insert(position, job);
+        self.touch(id);
+        id
+    }
+
+    pub fn remove(&mut self, id: usize) -> Option<Job> {
+        let position = self.jobs.iter().position(|job| job.id == id)?;
+        self.recent.retain(|&other| other != id);
+        Some(self.jobs.remove(position))
+    }
+
+    pub fn get(&self, id: usize) -> Option<&Job> {
+        self.jobs.iter().find(|job| job.id == id)
+    }
+
+    pub fn get_mut(&mut self, id: usize) -> Option<&mut Job> {
+        self.jobs.iter_mut().find(|job| job.id == id)
+    }
+
+    pub fn ids(&self) -> Vec<usize> {
+        self.jobs.iter().map(Job::id).collect()
+    }
+
+    /// Fait de `id` le job courant
+    pub fn touch(&mut self, id: usize) {
+        self.recent.retain(|&other| other != id);
+        self.recent.push(id);
+    }
+
+    fn current(&self) -> Option<usize> {
+        self.recent.last().copied()
+    }
+
+    fn previous(&self) -> Option<usize> {
+        self.recent.iter().rev().nth(1).copied()
+    }
+
+    /// Retrouve le job désigné par `spec` : `%n`, `%+` (ou `%%`, `%`), `%-`,
+    /// `%préfixe` ou `%?texte` ; le job courant si `spec` est absent.
+    pub fn resolve(&self, spec: Option<&str>) -> Result<usize, String> {
+        let Some(spec) = spec else {
+            return self.current().ok_or_else(|| "current: no such job".to_string());
+        };
+
+        let no_such_job = || format!("{}: no such job", spec);
+        let Some(name) = spec.strip_prefix('%') else {
+            return Err(no_such_job());
+        };
+
+        let found = match name {
+            "" | "%" | "+" => self.current(),
+            "-" => self.previous(),
+            _ if name.chars().all(|c| c.is_ascii_digit()) => {
+                name.parse().ok().filter(|&id| self.get(id).is_some())
+            }
+            _ => {
+                let matches: Vec<usize> = match name.strip_prefix('?') {
+                    Some(text) => self.find(|command| command.contains(text)),
+                    None => self.find(|command| command.starts_with(name)),
+                };
+                if matches.len() > 1 {
+                    return Err(format!("{}: ambiguous job spec", spec));
+                }
+                matches.first().copied()
+            }
+        };
+
+        found.ok_or_else(no_such_job)
+    }
+
+    fn find(&self, predicate: impl Fn(&str) -> bool) -> Vec<usize> {
+        self.jobs
+            .iter()
+            .filter(|job| predicate(&job.command))
+            .map(Job::id)
+            .collect()
+    }
+
+    /// Récupère sans bloquer les changements d'état des processus enfants
+    pub fn reap(&mut self) {
+        while let Ok(Some((pid, status))) = process::wait_status(-1, true) {
+            for job in &mut self.jobs {
+                if job.update(pid, status) {
+                    break;
+                }
+            }
+        }
+    }
+
+    /// Ligne décrivant le job, au format de `jobs` (`-l` : avec son PID)
+    pub fn format(&self, id: usize, with_pid: bool) -> String {
+        let Some(job) = self.get(id) else {
+            return String::new();
+        };
+
+        let marker = if Some(id) == self.current() {
+            '+'
+        } else if Some(id) == self.previous() {
+            '-'
+        } else {
+            ' '
+        };
+        let pid = match job.pgid.filter(|_| with_pid) {
+            Some(pid) => format!(" {}", pid),
+            None => " ".to_string(),
+        };
+        let background = if job.state() == JobState::Running { " &" } else { "" };
+
+        format!("[{}]{}{} {:<24}{}{}", id, marker, pid, job.describe_state(), job.command, background)
+    }
+
+    /// Note que l'état actuel du job a été affiché
+    pub fn mark_reported(&mut self, id: usize) {
+        if let Some(job) = self.get_mut(id) {
+            job.reported = job.state();
+        }
+    }
+
+    /// Lignes à afficher avant l'invite pour les jobs terminés ou suspendus
+    /// depuis la dernière notification ; les jobs terminés sont retirés.
+    pub fn notifications(&mut self) -> Vec<String> {
+        let mut lines = Vec::new();
+        for id in self.ids() {
+            let Some(job) = self.get(id) else {
+                continue;
+            };
+            if job.state() != job.reported {
+                lines.push(self.format(id, false));
+                self.mark_reported(id);
+            }
+        }
+
+        self.remove_finished();
+        lines
+    }
+
+    /// Retire les jobs terminés dont la fin a été affichée
+    pub fn remove_finished(&mut self) {
+        for id in self.ids() {
+            if self.get(id).is_some_and(|job| job.reported == JobState::Done) {
+                self.remove(id);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Table contenant un job par commande, le dernier étant le job courant
+    fn table(commands: &[&str]) -> JobTable {
+        let mut jobs = JobTable::default();
+        for command in commands {
+            jobs.insert(Job::new(command.to_string()));
+        }
+        jobs
+    }
+
+    #[test]
+    fn numbers() {
+        let mut jobs = table(&["sleep 1", "sleep 2", "vim notes"]);
+        assert_eq!(jobs.resolve(Some("%1")), Ok(1));
+        assert_eq!(jobs.resolve(Some("%3")), Ok(3));
+        assert_eq!(jobs.resolve(Some("%4")), Err("%4: no such job".to_string()));
+        assert_eq!(jobs.resolve(Some("1")), Err("1: no such job".to_string()));
+
+        // Un numéro libéré n'est réattribué qu'au-delà du plus grand
+        jobs.remove(2);
+        assert_eq!(jobs.insert(Job::new("make".to_string())), 4);
+        assert_eq!(jobs.resolve(Some("%2")), Err("%2: no such job".to_string()));
+    }
+
+    #[test]
+    fn current_and_previous() {
+        let mut jobs = table(&["sleep 1", "sleep 2", "vim notes"]);
+        for spec in [None, Some("%"), Some("%%"), Some("%+")] {
+            assert_eq!(jobs.resolve(spec), Ok(3));
+        }
+        assert_eq!(jobs.resolve(Some("%-")), Ok(2));
+
+        jobs.touch(1);
+        assert_eq!(jobs.resolve(Some("%+")), Ok(1));
+        assert_eq!(jobs.resolve(Some("%-")), Ok(3));
+
+        jobs.remove(1);
+        assert_eq!(jobs.resolve(Some("%+")), Ok(3));
+        assert_eq!(jobs.resolve(Some("%-")), Ok(2));
+
+        let empty = JobTable::default();
+        assert_eq!(empty.resolve(None), Err("current: no such job".to_string()));
+        assert_eq!(empty.resolve(Some("%-")), Err("%-: no such job".to_string()));
+    }
+
+    #[test]
+    fn names() {
+        let jobs = table(&["sleep 1", "sleep 2", "vim notes"]);
+        assert_eq!(jobs.resolve(Some("%vim")), Ok(3));
+        assert_eq!(jobs.resolve(Some("%?notes")), Ok(3));
+        assert_eq!(jobs.resolve(Some("%?2")), Ok(2));
+        assert_eq!(jobs.resolve(Some("%sleep")), Err("%sleep: ambiguous job spec".to_string()));
+        assert_eq!(jobs.resolve(Some("%emacs")), Err("%emacs: no such job".to_string()));
+    }
+}
@@ -1,3 +1,13 @@
+mod job;
+mod pipe;
+pub mod process;
 mod redirection;
+pub mod signals;
+mod substitution;
+pub mod terminal;
 
+pub use job::{Job, JobState, JobTable};
+pub use pipe::create_pipe;
 pub use redirection::RedirectionManager;
+pub use substitution::{ProcessSubstitution, SubstitutionKind};
+pub use terminal::Terminal;
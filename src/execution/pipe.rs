@@ -0,0 +1,30 @@
+use std::fs::File;
+use std::io;
+use std::os::unix::io::{FromRawFd, RawFd};
+
+/// Crée un pipe anonyme et retourne ses extrémités (lecture, écriture).
+///
+/// Les descripteurs sont marqués `FD_CLOEXEC` : seul le processus auquel on
+/// les branche explicitement (via `dup2` ou `Stdio`) les garde après `exec`.
+pub fn create_pipe() -> io::Result<(File, File)> {
+    let mut fds: [RawFd; 2] = [0; 2];
+
+    if unsafe { libc::pipe(fds.as_mut_ptr()) } == -1 {
+        return Err(io::Error::last_os_error());
+    }
+
+    for fd in fds {
+        if unsafe { libc::fcntl(fd, libc::F_SETFD, libc::FD_CLOEXEC) } == -1 {
+            let err = io::Error::last_os_error();
+            unsafe {
+                libc::close(fds[0]);
+                libc::close(fds[1]);
+            }
+            return Err(err);
+        }
+    }
+
+    let reader = unsafe { File::from_raw_fd(fds[0]) };
+    let writer = unsafe { File::from_raw_fd(fds[1]) };
+    Ok((reader, writer))
+}
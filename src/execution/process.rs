@@ -0,0 +1,123 @@
+use std::io::{self, Write};
+use std::os::unix::io::RawFd;
+use std::os::unix::process::ExitStatusExt;
+use std::process::ExitStatus;
+
+/// Identifiant d'un processus enfant
+pub type Pid = libc::pid_t;
+
+/// Code de retour d'une ligne de commande syntaxiquement invalide
+pub const STATUS_SYNTAX_ERROR: i32 = 2;
+/// Code de retour d'une commande trouvée mais impossible à exécuter
+pub const STATUS_NOT_EXECUTABLE: i32 = 126;
+/// Code de retour d'une commande introuvable
+pub const STATUS_NOT_FOUND: i32 = 127;
+
+/// Duplique le processus courant.
+///
+/// Retourne `Some(pid)` dans le parent et `None` dans l'enfant.
+pub fn fork() -> io::Result<Option<Pid>> {
+    // Vider les tampons pour ne pas dupliquer de sortie en attente
+    io::stdout().flush()?;
+    io::stderr().flush()?;
+
+    match unsafe { libc::fork() } {
+        -1 => Err(io::Error::last_os_error()),
+        0 => Ok(None),
+        pid => Ok(Some(pid)),
+    }
+}
+
+/// Termine immédiatement un processus enfant issu de `fork`, sans exécuter
+/// les destructeurs hérités du parent.
+pub fn exit_child(code: i32) -> ! {
+    let _ = io::stdout().flush();
+    let _ = io::stderr().flush();
+    unsafe { libc::_exit(code) }
+}
+
+/// Remplace le descripteur `target` par une copie de `source`.
+pub fn redirect_fd(source: RawFd, target: RawFd) -> io::Result<()> {
+    if source != target && unsafe { libc::dup2(source, target) } == -1 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(())
+}
+
+/// Convertit le statut d'un processus terminé en code de retour shell
+/// (128 + numéro du signal s'il a été tué).
+pub fn status_code(status: ExitStatus) -> i32 {
+    status
+        .code()
+        .unwrap_or_else(|| 128 + status.signal().unwrap_or(0))
+}
+
+/// Code de retour d'une commande externe dont le lancement a échoué
+pub fn spawn_error_status(err: &io::Error) -> i32 {
+    match err.kind() {
+        io::ErrorKind::NotFound => STATUS_NOT_FOUND,
+        _ => STATUS_NOT_EXECUTABLE,
+    }
+}
+
+/// Attend la fin d'un processus enfant et retourne son code de retour
+pub fn wait_for(pid: Pid) -> io::Result<i32> {
+    let mut status = 0;
+    loop {
+        if unsafe { libc::waitpid(pid, &mut status, 0) } != -1 {
+            return Ok(status_code(ExitStatus::from_raw(status)));
+        }
+
+        let err = io::Error::last_os_error();
+        if err.kind() != io::ErrorKind::Interrupted {
+            return Err(err);
+        }
+    }
+}
+
+/// Changement d'état d'un processus enfant rapporté par `waitpid`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WaitStatus {
+    /// Terminé normalement avec ce code
+    Exited(i32),
+    /// Tué par ce signal
+    Signaled(i32),
+    /// Suspendu (`SIGTSTP`, `SIGTTIN`...)
+    Stopped,
+}
+
+impl WaitStatus {
+    fn from_raw(status: libc::c_int) -> Self {
+        if libc::WIFSTOPPED(status) {
+            Self::Stopped
+        } else if libc::WIFSIGNALED(status) {
+            Self::Signaled(libc::WTERMSIG(status))
+        } else {
+            Self::Exited(libc::WEXITSTATUS(status))
+        }
+    }
+}
+
+/// Attend un changement d'état de `pid` (`-1` : n'importe quel enfant),
+/// suspensions comprises. Avec `nohang`, retourne `None` si aucun enfant n'a
+/// changé d'état.
+pub fn wait_status(pid: Pid, nohang: bool) -> io::Result<Option<(Pid, WaitStatus)>> {
+    let mut flags = libc::WUNTRACED;
+    if nohang {
+        flags |= libc::WNOHANG;
+    }
+
+    let mut status = 0;
+    loop {
+        match unsafe { libc::waitpid(pid, &mut status, flags) } {
+            0 => return Ok(None),
+            -1 => {
+                let err = io::Error::last_os_error();
+                if err.kind() != io::ErrorKind::Interrupted {
+                    return Err(err);
+                }
+            }
+            pid => return Ok(Some((pid, WaitStatus::from_raw(status)))),
+        }
+    }
+}
@@ -1,66 +1,349 @@
-use crate::parser::Redirection;
-use anyhow::Result;
-use std::fs::OpenOptions;
-use std::io::{self, Write};
+use crate::parser::{FileDescriptor, RedirectMode, Redirection};
+use crate::utils::error::strerror;
+use anyhow::{anyhow, bail, Result};
+use std::fs::{File, OpenOptions};
+use std::io::{self, Read, Seek, SeekFrom, Write};
+use std::mem::ManuallyDrop;
+use std::os::unix::io::{AsRawFd, FromRawFd, OwnedFd, RawFd};
+use std::os::unix::process::CommandExt;
+use std::process::Command;
+use std::rc::Rc;
+use std::sync::atomic::{AtomicUsize, Ordering};
 
-/// Structure pour gérer les redirections stdout/stderr
+/// Nombre de descripteurs redirigeables (0 à 9)
+const FD_COUNT: usize = FileDescriptor::MAX as usize + 1;
+
+/// Avant un `exec`, les sources des redirections sont déplacées au-delà des
+/// descripteurs redirigeables pour qu'aucun `dup2` n'en écrase une autre
+const FD_SAFE_MIN: RawFd = FD_COUNT as RawFd;
+
+/// Destination d'un descripteur de la commande
+#[derive(Clone)]
+enum FdTarget {
+    /// Descripteur du shell, partagé tel quel
+    Inherited(RawFd),
+    /// Fichier ouvert par une redirection, partagé entre ses duplicatas
+    File(Rc<File>),
+    /// Descripteur fermé par `n>&-`
+    Closed,
+}
+
+impl FdTarget {
+    /// Accès temporaire à un descripteur hérité sans en prendre possession
+    fn borrow_raw(fd: RawFd) -> ManuallyDrop<File> {
+        ManuallyDrop::new(unsafe { File::from_raw_fd(fd) })
+    }
+
+    fn closed_error() -> io::Error {
+        io::Error::from_raw_os_error(libc::EBADF)
+    }
+}
+
+impl Read for FdTarget {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        match self {
+            // Lecture directe, sans le tampon de `io::stdin()` : rien au-delà
+            // de ce qui est demandé n'est retiré de l'entrée partagée avec
+            // les commandes suivantes
+            Self::Inherited(fd) => Self::borrow_raw(*fd).read(buf),
+            Self::File(file) => (&**file).read(buf),
+            Self::Closed => Err(Self::closed_error()),
+        }
+    }
+}
+
+impl Write for FdTarget {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match self {
+            Self::Inherited(libc::STDOUT_FILENO) => io::stdout().write(buf),
+            Self::Inherited(libc::STDERR_FILENO) => io::stderr().write(buf),
+            Self::Inherited(fd) => Self::borrow_raw(*fd).write(buf),
+            Self::File(file) => (&**file).write(buf),
+            Self::Closed => Err(Self::closed_error()),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match self {
+            Self::Inherited(libc::STDOUT_FILENO) => io::stdout().flush(),
+            Self::Inherited(libc::STDERR_FILENO) => io::stderr().flush(),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Table des descripteurs 0 à 9 d'une commande.
+///
+/// Les redirections sont appliquées de gauche à droite comme en POSIX sh :
+/// `2>&1 > f` envoie stderr vers l'ancien stdout, `> f 2>&1` vers `f`.
 pub struct RedirectionManager {
-    stdout: Box<dyn Write>,
-    stderr: Box<dyn Write>,
+    fds: [FdTarget; FD_COUNT],
 }
 
 impl RedirectionManager {
-    /// Crée un nouveau gestionnaire de redirections avec stdout et stderr standard
+    /// Crée un gestionnaire où chaque descripteur est hérité du shell
     pub fn new() -> Self {
         Self {
-            stdout: Box::new(io::stdout()),
-            stderr: Box::new(io::stderr()),
+            fds: std::array::from_fn(|fd| FdTarget::Inherited(fd as RawFd)),
         }
     }
 
     /// Configure les redirections en fonction des paramètres fournis
-    pub fn with_redirections(
-        stdout_redirect: Option<&Redirection>,
-        stderr_redirect: Option<&Redirection>,
-    ) -> Result<Self> {
-        let stdout = Self::open_redirect(stdout_redirect)?
-            .unwrap_or_else(|| Box::new(io::stdout()));
-
-        let stderr = Self::open_redirect(stderr_redirect)?
-            .unwrap_or_else(|| Box::new(io::stdout()));
-
-        Ok(Self { stdout, stderr })
-    }
-
-    /// Ouvre un fichier pour une redirection donnée
-    fn open_redirect(redirect: Option<&Redirection>) -> Result<Option<Box<dyn Write>>> {
-        match redirect {
-            Some(redir) => {
-                // Créer les dossiers parents si nécessaire
-                if let Some(parent) = std::path::Path::new(&redir.target).parent() {
-                    std::fs::create_dir_all(parent)?;
+    pub fn with_redirections(redirections: &[Redirection]) -> Result<Self> {
+        let mut manager = Self::new();
+        manager.apply_all(redirections)?;
+        Ok(manager)
+    }
+
+    /// Branche un fichier déjà ouvert (ex: extrémité de pipe) sur `fd`
+    pub fn set_file(&mut self, fd: FileDescriptor, file: File) {
+        self.fds[fd.index()] = FdTarget::File(Rc::new(file));
+    }
+
+    /// Applique les redirections dans l'ordre où elles ont été écrites
+    pub fn apply_all(&mut self, redirections: &[Redirection]) -> Result<()> {
+        for redirection in redirections {
+            self.apply(redirection)?;
+        }
+        Ok(())
+    }
+
+    fn apply(&mut self, redirection: &Redirection) -> Result<()> {
+        let target = match redirection.mode {
+            RedirectMode::Overwrite | RedirectMode::Append => {
+                FdTarget::File(Rc::new(Self::open_output(redirection)?))
+            }
+            RedirectMode::Input => FdTarget::File(Rc::new(Self::open_input(redirection)?)),
+            RedirectMode::Duplicate => self.duplicate(&redirection.target)?,
+            RedirectMode::HereDoc => {
+                FdTarget::File(Rc::new(Self::here_document(&redirection.target)?))
+            }
+            RedirectMode::HereString => {
+                FdTarget::File(Rc::new(Self::here_document(&format!("{}\n", redirection.target))?))
+            }
+        };
+
+        self.fds[redirection.fd.index()] = target;
+        Ok(())
+    }
+
+    /// Résout la cible de `n>&m` : une copie de ce vers quoi pointe `m`
+    fn duplicate(&self, source: &str) -> Result<FdTarget> {
+        if source == "-" {
+            return Ok(FdTarget::Closed);
+        }
+
+        let fd = FileDescriptor::from_str(source)
+            .map_err(|_| anyhow!("{}: ambiguous redirect", source))?;
+
+        match &self.fds[fd.index()] {
+            FdTarget::Closed => bail!("{}: Bad file descriptor", source),
+            FdTarget::Inherited(raw) if unsafe { libc::fcntl(*raw, libc::F_GETFD) } == -1 => {
+                bail!("{}: Bad file descriptor", source)
+            }
+            target => Ok(target.clone()),
+        }
+    }
+
+    /// Ouvre un fichier pour une redirection `>` ou `>>`
+    fn open_output(redirect: &Redirection) -> Result<File> {
+        // Créer les dossiers parents si nécessaire
+        if let Some(parent) = std::path::Path::new(&redirect.target).parent() {
+            std::fs::create_dir_all(parent)
+                .map_err(|err| anyhow!("{}: {}", redirect.target, strerror(&err)))?;
+        }
+
+        OpenOptions::new()
+            .write(true)
+            .create(true)
+            .append(redirect.is_append())
+            .truncate(redirect.is_overwrite())
+            .open(&redirect.target)
+            .map_err(|err| anyhow!("{}: {}", redirect.target, strerror(&err)))
+    }
+
+    /// Ouvre en lecture le fichier d'une redirection `<`
+    fn open_input(redirect: &Redirection) -> Result<File> {
+        File::open(&redirect.target).map_err(|err| anyhow!("{}: {}", redirect.target, strerror(&err)))
+    }
+
+    /// Place le contenu d'un here-document dans un fichier temporaire anonyme,
+    /// rembobiné, qui servira d'entrée à la commande. Contrairement à un pipe,
+    /// un fichier n'impose aucune limite de taille au corps.
+    fn here_document(content: &str) -> Result<File> {
+        static COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+        let path = std::env::temp_dir().join(format!(
+            "shell-heredoc-{}-{}",
+            std::process::id(),
+            COUNTER.fetch_add(1, Ordering::Relaxed)
+        ));
+
+        let mut file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create_new(true)
+            .open(&path)
+            .map_err(|err| anyhow!("cannot create temp file for here-document: {}", strerror(&err)))?;
+        std::fs::remove_file(&path)?;
+
+        file.write_all(content.as_bytes())?;
+        file.seek(SeekFrom::Start(0))?;
+        Ok(file)
+    }
+
+    /// Reporte la table sur une commande externe : les `dup2` sont faits
+    /// dans le processus fils, juste avant `exec`.
+    pub fn configure_command(&self, cmd: &mut Command) -> Result<()> {
+        let (sources, plan) = self.plan()?;
+        if plan.is_empty() {
+            return Ok(());
+        }
+
+        unsafe {
+            cmd.pre_exec(move || {
+                // Les sources restent ouvertes jusqu'au `exec` (FD_CLOEXEC)
+                let _keep_alive = &sources;
+                for &(fd, source) in &plan {
+                    let result = match source {
+                        Some(source) => libc::dup2(source, fd),
+                        None => libc::close(fd),
+                    };
+                    if result == -1 && source.is_some() {
+                        return Err(io::Error::last_os_error());
+                    }
                 }
+                Ok(())
+            });
+        }
 
-                let file = OpenOptions::new()
-                    .write(true)
-                    .create(true)
-                    .append(redir.is_append())
-                    .truncate(redir.is_overwrite())
-                    .open(&redir.target)?;
+        Ok(())
+    }
+
+    /// Applique la table aux descripteurs du shell lui-même, pour une
+    /// commande composée (`if ... fi > fichier`) exécutée sans `fork`. Les
+    /// descripteurs d'origine sont rétablis à la destruction du résultat.
+    pub fn redirect_shell(&self) -> Result<SavedFds> {
+        let (sources, plan) = self.plan()?;
+        let mut saved = SavedFds { fds: Vec::new() };
+        flush_std_streams();
+
+        for (fd, source) in plan {
+            // Un descripteur déjà fermé le redeviendra
+            let original = Self::dup_above_safe_min(fd).ok();
+            saved.fds.push((fd, original));
 
-                Ok(Some(Box::new(file)))
+            let result = match source {
+                Some(source) => unsafe { libc::dup2(source, fd) },
+                None => unsafe { libc::close(fd) },
+            };
+            if result == -1 && source.is_some() {
+                bail!("{}: {}", fd, io::Error::last_os_error());
             }
-            None => Ok(None),
         }
+
+        drop(sources);
+        Ok(saved)
+    }
+
+    /// Sources des descripteurs redirigés, dupliquées hors de la plage 0-9
+    /// avant que le moindre `dup2` ne les écrase, et couples (descripteur,
+    /// source) à appliquer dans l'ordre ; `None` ferme le descripteur.
+    #[allow(clippy::type_complexity)]
+    fn plan(&self) -> Result<(Vec<OwnedFd>, Vec<(RawFd, Option<RawFd>)>)> {
+        let mut sources: Vec<OwnedFd> = Vec::new();
+        let mut plan: Vec<(RawFd, Option<RawFd>)> = Vec::new();
+
+        for (fd, target) in self.fds.iter().enumerate() {
+            let fd = fd as RawFd;
+            let source = match target {
+                FdTarget::Inherited(raw) if *raw == fd => continue,
+                FdTarget::Inherited(raw) => Some(Self::dup_above_safe_min(*raw)?),
+                FdTarget::File(file) => Some(Self::dup_above_safe_min(file.as_raw_fd())?),
+                FdTarget::Closed => None,
+            };
+
+            plan.push((fd, source.as_ref().map(AsRawFd::as_raw_fd)));
+            sources.extend(source);
+        }
+
+        Ok((sources, plan))
+    }
+
+    /// Duplique `fd` vers un numéro hors de la plage 0-9, en `FD_CLOEXEC`
+    fn dup_above_safe_min(fd: RawFd) -> Result<OwnedFd> {
+        let duplicated = unsafe { libc::fcntl(fd, libc::F_DUPFD_CLOEXEC, FD_SAFE_MIN) };
+        if duplicated == -1 {
+            bail!("{}: {}", fd, io::Error::last_os_error());
+        }
+        Ok(unsafe { OwnedFd::from_raw_fd(duplicated) })
+    }
+
+    /// Retourne une référence mutable vers stdin
+    pub fn stdin(&mut self) -> &mut dyn Read {
+        &mut self.fds[FileDescriptor::STDIN.index()]
     }
 
     /// Retourne une référence mutable vers stdout
     pub fn stdout(&mut self) -> &mut dyn Write {
-        &mut *self.stdout
+        &mut self.fds[FileDescriptor::STDOUT.index()]
     }
 
     /// Retourne une référence mutable vers stderr
     pub fn stderr(&mut self) -> &mut dyn Write {
-        &mut *self.stderr
+        &mut self.fds[FileDescriptor::STDERR.index()]
+    }
+}
+
+/// Descripteurs du shell remplacés par `RedirectionManager::redirect_shell`
+pub struct SavedFds {
+    /// Descripteur et copie de sa valeur d'origine (`None` s'il était fermé)
+    fds: Vec<(RawFd, Option<OwnedFd>)>,
+}
+
+impl Drop for SavedFds {
+    fn drop(&mut self) {
+        // Ce qui a été écrit pendant la redirection va à sa destination
+        flush_std_streams();
+        for (fd, original) in self.fds.drain(..).rev() {
+            match original {
+                Some(original) => unsafe { libc::dup2(original.as_raw_fd(), fd) },
+                None => unsafe { libc::close(fd) },
+            };
+        }
+    }
+}
+
+fn flush_std_streams() {
+    let _ = io::stdout().flush();
+    let _ = io::stderr().flush();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stdin_reads_the_redirected_file() {
+        let path = std::env::temp_dir().join(format!("shell-redirection-test-{}", std::process::id()));
+        std::fs::write(&path, "first line\nsecond line\n").unwrap();
+
+        let target = path.to_string_lossy().into_owned();
+        let redirection = Redirection::new(FileDescriptor::STDIN, target, RedirectMode::Input);
+        let mut manager = RedirectionManager::with_redirections(&[redirection]).unwrap();
+
+        let mut content = String::new();
+        manager.stdin().read_to_string(&mut content).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(content, "first line\nsecond line\n");
+    }
+
+    #[test]
+    fn closed_stdin_cannot_be_read() {
+        let redirection = Redirection::new(FileDescriptor::STDIN, "-".to_string(), RedirectMode::Duplicate);
+        let mut manager = RedirectionManager::with_redirections(&[redirection]).unwrap();
+
+        let error = manager.stdin().read(&mut [0u8; 1]).unwrap_err();
+        assert_eq!(error.raw_os_error(), Some(libc::EBADF));
     }
 }
@@ -0,0 +1,87 @@
+use std::io;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use crate::execution::process::Pid;
+
+/// Signaux de contrôle des jobs ignorés par le shell interactif : il ne doit
+/// ni être suspendu par Ctrl-Z, ni l'être en reprenant le terminal.
+const JOB_CONTROL_SIGNALS: [libc::c_int; 3] = [libc::SIGTSTP, libc::SIGTTIN, libc::SIGTTOU];
+
+/// Ctrl-C reçu depuis le dernier appel à `take_interrupt`
+static INTERRUPTED: AtomicBool = AtomicBool::new(false);
+
+extern "C" fn on_interrupt(_signal: libc::c_int) {
+    INTERRUPTED.store(true, Ordering::SeqCst);
+}
+
+pub fn ignore_job_control_signals() {
+    for signal in JOB_CONTROL_SIGNALS {
+        unsafe { libc::signal(signal, libc::SIG_IGN) };
+    }
+}
+
+/// Protège le shell interactif du clavier : Ctrl-C (`SIGINT`) est seulement
+/// noté, et interrompt la lecture en cours (pas de `SA_RESTART`) ; Ctrl-\
+/// (`SIGQUIT`) est ignoré. Les commandes lancées reçoivent ces signaux
+/// normalement.
+pub fn install_interrupt_handlers() {
+    unsafe {
+        let mut action: libc::sigaction = std::mem::zeroed();
+        action.sa_sigaction = on_interrupt as extern "C" fn(libc::c_int) as libc::sighandler_t;
+        libc::sigemptyset(&mut action.sa_mask);
+        libc::sigaction(libc::SIGINT, &action, std::ptr::null_mut());
+        libc::signal(libc::SIGQUIT, libc::SIG_IGN);
+    }
+}
+
+/// Indique si Ctrl-C a été reçu depuis le dernier appel, et l'oublie
+pub fn take_interrupt() -> bool {
+    INTERRUPTED.swap(false, Ordering::SeqCst)
+}
+
+/// Rétablit le comportement par défaut des signaux dans un processus fils :
+/// ceux ignorés par le shell le resteraient sinon après `exec`. Les signaux
+/// de contrôle des jobs ne sont rétablis que pour un processus lancé comme
+/// job, qui peut alors être suspendu.
+pub fn restore_default_signals(job: bool) {
+    for signal in [libc::SIGINT, libc::SIGQUIT] {
+        unsafe { libc::signal(signal, libc::SIG_DFL) };
+    }
+    if job {
+        for signal in JOB_CONTROL_SIGNALS {
+            unsafe { libc::signal(signal, libc::SIG_DFL) };
+        }
+    }
+}
+
+/// Envoie `signal` à tous les processus du groupe `pgid`
+pub fn signal_group(pgid: Pid, signal: libc::c_int) -> io::Result<()> {
+    if unsafe { libc::kill(-pgid, signal) } == -1 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(())
+}
+
+/// Place le processus `pid` (0 : le processus courant) dans le groupe
+/// `pgid` (0 : un nouveau groupe dont il est le leader)
+pub fn set_process_group(pid: Pid, pgid: Pid) -> io::Result<()> {
+    if unsafe { libc::setpgid(pid, pgid) } == -1 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(())
+}
+
+/// Libellé d'un job tué par `signal`, tel qu'affiché par `jobs`
+pub fn describe(signal: libc::c_int) -> String {
+    match signal {
+        libc::SIGHUP => "Hangup".to_string(),
+        libc::SIGINT => "Interrupt".to_string(),
+        libc::SIGQUIT => "Quit".to_string(),
+        libc::SIGABRT => "Aborted".to_string(),
+        libc::SIGKILL => "Killed".to_string(),
+        libc::SIGSEGV => "Segmentation fault".to_string(),
+        libc::SIGPIPE => "Broken pipe".to_string(),
+        libc::SIGTERM => "Terminated".to_string(),
+        signal => format!("Signal {}", signal),
+    }
+}
@@ -0,0 +1,55 @@
+use crate::execution::process::Pid;
+use std::fs::File;
+use std::io;
+use std::os::unix::io::{AsRawFd, FromRawFd, OwnedFd, RawFd};
+
+/// Premier descripteur utilisé pour les substitutions, hors de la plage des
+/// redirections (0 à 9)
+const FIRST_FD: RawFd = 10;
+
+/// Direction d'une substitution de processus
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SubstitutionKind {
+    /// `<(cmd)` : la commande extérieure lit la sortie de `cmd`
+    Input,
+    /// `>(cmd)` : ce que la commande extérieure écrit alimente `cmd`
+    Output,
+}
+
+/// Processus lancé par `<(cmd)` ou `>(cmd)`, avec l'extrémité de pipe que le
+/// shell garde ouverte pour la commande extérieure.
+#[derive(Debug)]
+pub struct ProcessSubstitution {
+    pid: Pid,
+    file: File,
+}
+
+impl ProcessSubstitution {
+    /// `end` est l'extrémité du pipe destinée à la commande extérieure ; elle
+    /// est déplacée au-delà de 9 et héritée par les commandes lancées
+    pub fn new(pid: Pid, end: File) -> io::Result<Self> {
+        let fd = unsafe { libc::fcntl(end.as_raw_fd(), libc::F_DUPFD, FIRST_FD) };
+        if fd == -1 {
+            return Err(io::Error::last_os_error());
+        }
+
+        Ok(Self {
+            pid,
+            file: File::from(unsafe { OwnedFd::from_raw_fd(fd) }),
+        })
+    }
+
+    /// Chemin donné à la commande à la place de `<(cmd)`
+    pub fn path(&self) -> String {
+        format!("/dev/fd/{}", self.file.as_raw_fd())
+    }
+
+    pub fn raw_fd(&self) -> RawFd {
+        self.file.as_raw_fd()
+    }
+
+    /// Ferme l'extrémité du shell et retourne le PID à attendre
+    pub fn close(self) -> Pid {
+        self.pid
+    }
+}
@@ -0,0 +1,86 @@
+use std::io;
+use termios::{tcsetattr, Termios, ECHO, ICANON, TCSADRAIN};
+
+use crate::execution::process::Pid;
+
+/// Indique si l'entrée standard est un terminal
+pub fn is_terminal() -> bool {
+    unsafe { libc::isatty(libc::STDIN_FILENO) == 1 }
+}
+
+/// Donne le terminal au groupe `pgid` : ses processus reçoivent alors les
+/// caractères tapés et les signaux du clavier (Ctrl-C, Ctrl-Z).
+pub fn set_foreground(pgid: Pid) -> io::Result<()> {
+    if !is_terminal() {
+        return Ok(());
+    }
+    if unsafe { libc::tcsetpgrp(libc::STDIN_FILENO, pgid) } == -1 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(())
+}
+
+/// Paramètres du terminal partagé entre le shell et ses jobs.
+///
+/// Le shell lit ses lignes en mode raw (sans écho ni mode canonique) pour
+/// gérer lui-même l'édition ; les commandes qu'il lance retrouvent les
+/// paramètres d'origine (mode "cooked"), ou ceux qu'elles avaient lors de
+/// leur suspension.
+pub struct Terminal {
+    /// Paramètres d'origine ; `None` si l'entrée n'est pas un terminal
+    saved: Option<Termios>,
+}
+
+impl Terminal {
+    pub fn new() -> Self {
+        let saved = if is_terminal() {
+            Termios::from_fd(libc::STDIN_FILENO).ok()
+        } else {
+            None
+        };
+        Self { saved }
+    }
+
+    /// Passe en mode raw pour la saisie d'une ligne
+    pub fn enter_raw_mode(&self) -> io::Result<()> {
+        let Some(saved) = self.saved else {
+            return Ok(());
+        };
+
+        let mut raw = saved;
+        raw.c_lflag &= !(ICANON | ECHO);
+        tcsetattr(libc::STDIN_FILENO, TCSADRAIN, &raw)
+    }
+
+    /// Rétablit les paramètres d'origine
+    pub fn restore(&self) -> io::Result<()> {
+        match &self.saved {
+            Some(saved) => tcsetattr(libc::STDIN_FILENO, TCSADRAIN, saved),
+            None => Ok(()),
+        }
+    }
+
+    /// Donne le terminal au job `pgid`, avec les paramètres qu'il avait
+    /// lors de sa suspension (`modes`) ou ceux d'origine.
+    pub fn give_to(&self, pgid: Pid, modes: Option<&Termios>) -> io::Result<()> {
+        match modes {
+            Some(modes) if self.saved.is_some() => tcsetattr(libc::STDIN_FILENO, TCSADRAIN, modes)?,
+            _ => self.restore()?,
+        }
+        set_foreground(pgid)
+    }
+
+    /// Reprend le terminal pour le shell (groupe `pgid`) une fois le job au
+    /// premier plan terminé ou suspendu, et rétablit les paramètres
+    /// d'origine. Retourne les paramètres laissés par le job.
+    pub fn reclaim(&self, pgid: Pid) -> io::Result<Option<Termios>> {
+        set_foreground(pgid)?;
+        if self.saved.is_none() {
+            return Ok(None);
+        }
+
+        let modes = Termios::from_fd(libc::STDIN_FILENO)?;
+        self.restore()?;
+        Ok(Some(modes))
+    }
+}
@@ -0,0 +1,625 @@
+use crate::variables::Variables;
+use anyhow::{anyhow, bail, Result};
+
+/// Profondeur maximale lorsqu'une variable contient elle-même une expression
+/// (`A=B+1`, `B=A`...), pour ne pas boucler indéfiniment
+const MAX_RECURSION: usize = 64;
+
+/// Opérateurs reconnus, les plus longs d'abord
+const OPERATORS: [&str; 39] = [
+    "<<=", ">>=", "**", "<<", ">>", "<=", ">=", "==", "!=", "&&", "||", "++", "--", "+=", "-=",
+    "*=", "/=", "%=", "&=", "^=", "|=", "+", "-", "*", "/", "%", "<", ">", "&", "^", "|", "!",
+    "~", "?", ":", "=", ",", "(", ")",
+];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Token {
+    Number(i64),
+    Name(String),
+    Operator(&'static str),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum BinaryOp {
+    Power,
+    Multiply,
+    Divide,
+    Remainder,
+    Add,
+    Subtract,
+    ShiftLeft,
+    ShiftRight,
+    Less,
+    LessEqual,
+    Greater,
+    GreaterEqual,
+    Equal,
+    NotEqual,
+    BitAnd,
+    BitXor,
+    BitOr,
+    /// `&&` et `||` n'évaluent leur opérande droite que si nécessaire
+    And,
+    Or,
+}
+
+impl BinaryOp {
+    /// Opérateur binaire et sa priorité (plus elle est haute, plus il lie fort)
+    fn from_operator(operator: &str) -> Option<(Self, u8)> {
+        let op = match operator {
+            "||" => (Self::Or, 1),
+            "&&" => (Self::And, 2),
+            "|" => (Self::BitOr, 3),
+            "^" => (Self::BitXor, 4),
+            "&" => (Self::BitAnd, 5),
+            "==" => (Self::Equal, 6),
+            "!=" => (Self::NotEqual, 6),
+            "<" => (Self::Less, 7),
+            "<=" => (Self::LessEqual, 7),
+            ">" => (Self::Greater, 7),
+            ">=" => (Self::GreaterEqual, 7),
+            "<<" => (Self::ShiftLeft, 8),
+            ">>" => (Self::ShiftRight, 8),
+            "+" => (Self::Add, 9),
+            "-" => (Self::Subtract, 9),
+            "*" => (Self::Multiply, 10),
+            "/" => (Self::Divide, 10),
+            "%" => (Self::Remainder, 10),
+            "**" => (Self::Power, 11),
+            _ => return None,
+        };
+        Some(op)
+    }
+
+    /// Opérateur combiné d'une affectation (`+=` → `+`) ; `Some(None)` pour `=`
+    fn from_assignment(operator: &str) -> Option<Option<Self>> {
+        match operator {
+            "=" => Some(None),
+            _ => {
+                let binary = operator.strip_suffix('=')?;
+                if matches!(binary, "" | "=" | "!" | "<" | ">") {
+                    return None;
+                }
+                Some(Some(Self::from_operator(binary)?.0))
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum UnaryOp {
+    Plus,
+    Minus,
+    Not,
+    BitNot,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Expr {
+    Number(i64),
+    Variable(String),
+    Unary(UnaryOp, Box<Expr>),
+    Binary(BinaryOp, Box<Expr>, Box<Expr>),
+    Conditional(Box<Expr>, Box<Expr>, Box<Expr>),
+    /// `NOM = valeur` ou `NOM op= valeur`
+    Assign {
+        name: String,
+        op: Option<BinaryOp>,
+        value: Box<Expr>,
+    },
+    /// `++NOM`, `NOM--`... ; `prefix` indique si la nouvelle valeur est retournée
+    Increment {
+        name: String,
+        delta: i64,
+        prefix: bool,
+    },
+    /// `a, b` : évalue les deux, retourne `b`
+    Comma(Box<Expr>, Box<Expr>),
+}
+
+/// Évalue une expression arithmétique entière (`$((...))`).
+///
+/// Les variables sont lues et modifiées dans `variables` ; une variable non
+/// définie ou vide vaut 0.
+pub fn evaluate(expression: &str, variables: &mut Variables) -> Result<i64> {
+    Evaluator {
+        variables,
+        depth: 0,
+    }
+    .evaluate(expression)
+}
+
+struct Evaluator<'a> {
+    variables: &'a mut Variables,
+    depth: usize,
+}
+
+impl Evaluator<'_> {
+    fn evaluate(&mut self, expression: &str) -> Result<i64> {
+        if expression.trim().is_empty() {
+            return Ok(0);
+        }
+
+        let result = Parser::parse(expression).and_then(|expr| self.eval(&expr));
+        result.map_err(|err| anyhow!("{}: {}", expression.trim(), err))
+    }
+
+    fn eval(&mut self, expr: &Expr) -> Result<i64> {
+        match expr {
+            Expr::Number(n) => Ok(*n),
+            Expr::Variable(name) => self.value_of(name),
+            Expr::Unary(op, operand) => {
+                let value = self.eval(operand)?;
+                match op {
+                    UnaryOp::Plus => Ok(value),
+                    UnaryOp::Minus => value.checked_neg().ok_or_else(overflow),
+                    UnaryOp::Not => Ok((value == 0) as i64),
+                    UnaryOp::BitNot => Ok(!value),
+                }
+            }
+            Expr::Binary(BinaryOp::And, left, right) => {
+                Ok((self.eval(left)? != 0 && self.eval(right)? != 0) as i64)
+            }
+            Expr::Binary(BinaryOp::Or, left, right) => {
+                Ok((self.eval(left)? != 0 || self.eval(right)? != 0) as i64)
+            }
+            Expr::Binary(op, left, right) => {
+                let left = self.eval(left)?;
+                let right = self.eval(right)?;
+                apply(*op, left, right)
+            }
+            Expr::Conditional(condition, then, otherwise) => {
+                if self.eval(condition)? != 0 {
+                    self.eval(then)
+                } else {
+                    self.eval(otherwise)
+                }
+            }
+            Expr::Assign { name, op, value } => {
+                let value = self.eval(value)?;
+                let value = match op {
+                    Some(op) => apply(*op, self.value_of(name)?, value)?,
+                    None => value,
+                };
+                self.variables.set(name, &value.to_string());
+                Ok(value)
+            }
+            Expr::Increment {
+                name,
+                delta,
+                prefix,
+            } => {
+                let old = self.value_of(name)?;
+                let new = old.checked_add(*delta).ok_or_else(overflow)?;
+                self.variables.set(name, &new.to_string());
+                Ok(if *prefix { new } else { old })
+            }
+            Expr::Comma(first, second) => {
+                self.eval(first)?;
+                self.eval(second)
+            }
+        }
+    }
+
+    /// Valeur numérique d'une variable ; si elle contient une expression,
+    /// celle-ci est évaluée à son tour
+    fn value_of(&mut self, name: &str) -> Result<i64> {
+        let value = self.variables.get(name).unwrap_or_default();
+        let value = value.trim();
+
+        if value.is_empty() {
+            return Ok(0);
+        }
+        if let Ok(n) = parse_number(value) {
+            return Ok(n);
+        }
+
+        if self.depth >= MAX_RECURSION {
+            bail!("expression recursion level exceeded");
+        }
+        self.depth += 1;
+        let result = self.evaluate(value);
+        self.depth -= 1;
+        result
+    }
+}
+
+fn overflow() -> anyhow::Error {
+    anyhow!("arithmetic overflow")
+}
+
+/// Applique un opérateur binaire (hors `&&` et `||`)
+fn apply(op: BinaryOp, left: i64, right: i64) -> Result<i64> {
+    let result = match op {
+        BinaryOp::Power => {
+            if right < 0 {
+                bail!("exponent less than 0");
+            }
+            u32::try_from(right)
+                .ok()
+                .and_then(|exponent| left.checked_pow(exponent))
+        }
+        BinaryOp::Multiply => left.checked_mul(right),
+        BinaryOp::Divide | BinaryOp::Remainder if right == 0 => bail!("division by 0"),
+        BinaryOp::Divide => left.checked_div(right),
+        BinaryOp::Remainder => left.checked_rem(right),
+        BinaryOp::Add => left.checked_add(right),
+        BinaryOp::Subtract => left.checked_sub(right),
+        BinaryOp::ShiftLeft => u32::try_from(right).ok().and_then(|n| left.checked_shl(n)),
+        BinaryOp::ShiftRight => u32::try_from(right).ok().and_then(|n| left.checked_shr(n)),
+        BinaryOp::Less => Some((left < right) as i64),
+        BinaryOp::LessEqual => Some((left <= right) as i64),
+        BinaryOp::Greater => Some((left > right) as i64),
+        BinaryOp::GreaterEqual => Some((left >= right) as i64),
+        BinaryOp::Equal => Some((left == right) as i64),
+        BinaryOp::NotEqual => Some((left != right) as i64),
+        BinaryOp::BitAnd => Some(left & right),
+        BinaryOp::BitXor => Some(left ^ right),
+        BinaryOp::BitOr => Some(left | right),
+        BinaryOp::And => Some((left != 0 && right != 0) as i64),
+        BinaryOp::Or => Some((left != 0 || right != 0) as i64),
+    };
+
+    result.ok_or_else(overflow)
+}
+
+/// Lit une constante : décimale, octale (`017`), hexadécimale (`0x1f`) ou
+/// en base explicite (`2#101`, bases 2 à 64)
+fn parse_number(text: &str) -> Result<i64> {
+    let (base, digits) = if let Some((base, digits)) = text.split_once('#') {
+        let base = base
+            .parse::<u32>()
+            .ok()
+            .filter(|base| (2..=64).contains(base))
+            .ok_or_else(|| anyhow!("invalid arithmetic base (error token is \"{}\")", text))?;
+        (base, digits)
+    } else if let Some(hex) = text.strip_prefix("0x").or_else(|| text.strip_prefix("0X")) {
+        (16, hex)
+    } else if text.len() > 1 && text.starts_with('0') {
+        (8, &text[1..])
+    } else {
+        (10, text)
+    };
+
+    let values: Option<Vec<u32>> = digits.chars().map(|c| digit_value(c, base)).collect();
+    let Some(values) = values.filter(|values| !values.is_empty()) else {
+        bail!("value too great for base (error token is \"{}\")", text);
+    };
+    values.into_iter().try_fold(0i64, |number, value| {
+        number
+            .checked_mul(base.into())
+            .and_then(|number| number.checked_add(value.into()))
+            .ok_or_else(overflow)
+    })
+}
+
+/// Valeur d'un chiffre dans `base` ; les chiffres sont dans l'ordre 0-9,
+/// a-z, A-Z, `@`, `_`, mais jusqu'à la base 36 une majuscule vaut la
+/// minuscule correspondante
+fn digit_value(c: char, base: u32) -> Option<u32> {
+    let value = match c {
+        '0'..='9' => c as u32 - '0' as u32,
+        'a'..='z' => c as u32 - 'a' as u32 + 10,
+        'A'..='Z' if base <= 36 => c as u32 - 'A' as u32 + 10,
+        'A'..='Z' => c as u32 - 'A' as u32 + 36,
+        '@' => 62,
+        '_' => 63,
+        _ => return None,
+    };
+    (value < base).then_some(value)
+}
+
+/// Analyseur à descente récursive, un niveau par priorité
+struct Parser {
+    tokens: Vec<Token>,
+    position: usize,
+}
+
+impl Parser {
+    fn parse(expression: &str) -> Result<Expr> {
+        let mut parser = Self {
+            tokens: tokenize(expression)?,
+            position: 0,
+        };
+
+        let expr = parser.parse_comma()?;
+        match parser.peek() {
+            None => Ok(expr),
+            Some(token) => bail!("syntax error in expression (error token is \"{}\")", token_text(token)),
+        }
+    }
+
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position)
+    }
+
+    fn peek_operator(&self) -> Option<&'static str> {
+        match self.peek() {
+            Some(Token::Operator(op)) => Some(op),
+            _ => None,
+        }
+    }
+
+    fn expect(&mut self, operator: &str) -> Result<()> {
+        if self.peek_operator() != Some(operator) {
+            bail!("syntax error: `{}' expected", operator);
+        }
+        self.position += 1;
+        Ok(())
+    }
+
+    fn parse_comma(&mut self) -> Result<Expr> {
+        let mut expr = self.parse_assignment()?;
+        while self.peek_operator() == Some(",") {
+            self.position += 1;
+            expr = Expr::Comma(Box::new(expr), Box::new(self.parse_assignment()?));
+        }
+        Ok(expr)
+    }
+
+    fn parse_assignment(&mut self) -> Result<Expr> {
+        if let (Some(Token::Name(name)), Some(Token::Operator(operator))) =
+            (self.peek(), self.tokens.get(self.position + 1))
+        {
+            if let Some(op) = BinaryOp::from_assignment(operator) {
+                let name = name.clone();
+                self.position += 2;
+                let value = Box::new(self.parse_assignment()?);
+                return Ok(Expr::Assign { name, op, value });
+            }
+        }
+
+        self.parse_conditional()
+    }
+
+    fn parse_conditional(&mut self) -> Result<Expr> {
+        let condition = self.parse_binary(1)?;
+        if self.peek_operator() != Some("?") {
+            return Ok(condition);
+        }
+
+        self.position += 1;
+        let then = self.parse_comma()?;
+        self.expect(":")?;
+        let otherwise = self.parse_conditional()?;
+        Ok(Expr::Conditional(
+            Box::new(condition),
+            Box::new(then),
+            Box::new(otherwise),
+        ))
+    }
+
+    /// Opérateurs binaires de priorité au moins `min_precedence` ; `**` est
+    /// associatif à droite, les autres à gauche
+    fn parse_binary(&mut self, min_precedence: u8) -> Result<Expr> {
+        let mut left = self.parse_unary()?;
+
+        while let Some((op, precedence)) = self.peek_operator().and_then(BinaryOp::from_operator) {
+            if precedence < min_precedence {
+                break;
+            }
+            self.position += 1;
+
+            let next_precedence = if op == BinaryOp::Power {
+                precedence
+            } else {
+                precedence + 1
+            };
+            let right = self.parse_binary(next_precedence)?;
+            left = Expr::Binary(op, Box::new(left), Box::new(right));
+        }
+
+        Ok(left)
+    }
+
+    fn parse_unary(&mut self) -> Result<Expr> {
+        let op = match self.peek_operator() {
+            Some("+") => UnaryOp::Plus,
+            Some("-") => UnaryOp::Minus,
+            Some("!") => UnaryOp::Not,
+            Some("~") => UnaryOp::BitNot,
+            Some(operator @ ("++" | "--")) => {
+                self.position += 1;
+                let Some(Token::Name(name)) = self.peek().cloned() else {
+                    bail!("syntax error: variable expected after `{}'", operator);
+                };
+                self.position += 1;
+                return Ok(Expr::Increment {
+                    name,
+                    delta: if operator == "++" { 1 } else { -1 },
+                    prefix: true,
+                });
+            }
+            _ => return self.parse_postfix(),
+        };
+
+        self.position += 1;
+        Ok(Expr::Unary(op, Box::new(self.parse_unary()?)))
+    }
+
+    fn parse_postfix(&mut self) -> Result<Expr> {
+        let primary = self.parse_primary()?;
+
+        if let Expr::Variable(name) = &primary {
+            if let Some(operator @ ("++" | "--")) = self.peek_operator() {
+                self.position += 1;
+                return Ok(Expr::Increment {
+                    name: name.clone(),
+                    delta: if operator == "++" { 1 } else { -1 },
+                    prefix: false,
+                });
+            }
+        }
+
+        Ok(primary)
+    }
+
+    fn parse_primary(&mut self) -> Result<Expr> {
+        let token = self
+            .peek()
+            .cloned()
+            .ok_or_else(|| anyhow!("syntax error: operand expected"))?;
+        self.position += 1;
+
+        match token {
+            Token::Number(n) => Ok(Expr::Number(n)),
+            Token::Name(name) => Ok(Expr::Variable(name)),
+            Token::Operator("(") => {
+                let expr = self.parse_comma()?;
+                self.expect(")")?;
+                Ok(expr)
+            }
+            Token::Operator(operator) => bail!(
+                "syntax error: operand expected (error token is \"{}\")",
+                operator
+            ),
+        }
+    }
+}
+
+fn token_text(token: &Token) -> String {
+    match token {
+        Token::Number(n) => n.to_string(),
+        Token::Name(name) => name.clone(),
+        Token::Operator(operator) => operator.to_string(),
+    }
+}
+
+fn tokenize(expression: &str) -> Result<Vec<Token>> {
+    let mut tokens = Vec::new();
+    let mut rest = expression;
+
+    while let Some(c) = rest.chars().next() {
+        if c.is_whitespace() {
+            rest = &rest[c.len_utf8()..];
+            continue;
+        }
+
+        if c.is_ascii_alphanumeric() || c == '_' {
+            // `#` n'apparaît que dans une constante en base explicite, dont
+            // les chiffres peuvent aussi comprendre `@`
+            let is_number = c.is_ascii_digit();
+            let mut explicit_base = false;
+            let len = rest
+                .find(|c: char| {
+                    explicit_base |= is_number && c == '#';
+                    !(c.is_ascii_alphanumeric() || c == '_' || (explicit_base && matches!(c, '#' | '@')))
+                })
+                .unwrap_or(rest.len());
+            let (word, remaining) = rest.split_at(len);
+
+            tokens.push(if is_number {
+                Token::Number(parse_number(word)?)
+            } else {
+                Token::Name(word.to_string())
+            });
+            rest = remaining;
+            continue;
+        }
+
+        let Some(operator) = OPERATORS.iter().find(|op| rest.starts_with(*op)) else {
+            bail!("syntax error: invalid arithmetic operator (error token is \"{}\")", rest);
+        };
+        tokens.push(Token::Operator(operator));
+        rest = &rest[operator.len()..];
+    }
+
+    Ok(tokens)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn eval(expression: &str) -> Result<i64> {
+        evaluate(expression, &mut Variables::new())
+    }
+
+    fn error(expression: &str) -> String {
+        eval(expression).unwrap_err().to_string()
+    }
+
+    #[test]
+    fn precedence() {
+        assert_eq!(eval("1 + 2 * 3").unwrap(), 7);
+        assert_eq!(eval("(1 + 2) * 3").unwrap(), 9);
+        assert_eq!(eval("1 << 2 + 1").unwrap(), 8);
+        assert_eq!(eval("1 + 1 == 2 && 3 > 2").unwrap(), 1);
+        assert_eq!(eval("6 & 3 | 8 ^ 1").unwrap(), 11);
+        assert_eq!(eval("!0 + ~0").unwrap(), 0);
+        assert_eq!(eval("0 || 1 ? 10 : 20").unwrap(), 10);
+    }
+
+    #[test]
+    fn associativity() {
+        assert_eq!(eval("2 - 3 - 4").unwrap(), -5);
+        assert_eq!(eval("64 / 4 / 2").unwrap(), 8);
+        assert_eq!(eval("2 ** 3 ** 2").unwrap(), 512);
+        assert_eq!(eval("0 ? 1 : 0 ? 2 : 3").unwrap(), 3);
+    }
+
+    #[test]
+    fn assignments() {
+        let mut variables = Variables::new();
+        assert_eq!(evaluate("arith_x = 3, arith_x += 2", &mut variables).unwrap(), 5);
+        assert_eq!(evaluate("arith_x++", &mut variables).unwrap(), 5);
+        assert_eq!(evaluate("--arith_x", &mut variables).unwrap(), 5);
+        assert_eq!(variables.get("arith_x").as_deref(), Some("5"));
+        // Une variable non définie vaut 0, une variable contenant une
+        // expression est évaluée
+        variables.set("arith_y", "arith_x * 2");
+        assert_eq!(evaluate("arith_unset + arith_y", &mut variables).unwrap(), 10);
+    }
+
+    #[test]
+    fn division_by_zero() {
+        assert!(error("1 / 0").contains("division by 0"));
+        assert!(error("1 % 0").contains("division by 0"));
+        assert!(error("arith_z /= 0").contains("division by 0"));
+    }
+
+    #[test]
+    fn overflow_is_an_error() {
+        assert_eq!(eval("9223372036854775807").unwrap(), i64::MAX);
+        assert!(error("9223372036854775807 + 1").contains("overflow"));
+        assert!(error("-9223372036854775807 - 2").contains("overflow"));
+        assert!(error("(-9223372036854775807 - 1) / -1").contains("overflow"));
+        assert!(error("2 ** 64").contains("overflow"));
+        assert!(error("1 << 64").contains("overflow"));
+        assert!(error("99999999999999999999").contains("overflow"));
+        assert!(error("2 ** -1").contains("exponent less than 0"));
+    }
+
+    #[test]
+    fn bases() {
+        assert_eq!(eval("0x1f").unwrap(), 31);
+        assert_eq!(eval("0X1F").unwrap(), 31);
+        assert_eq!(eval("017").unwrap(), 15);
+        assert_eq!(eval("0").unwrap(), 0);
+        assert_eq!(eval("2#101").unwrap(), 5);
+        assert_eq!(eval("36#z").unwrap(), 35);
+        assert_eq!(eval("36#Z").unwrap(), 35);
+        assert_eq!(eval("16#fF").unwrap(), 255);
+        assert_eq!(eval("64#z").unwrap(), 35);
+        assert_eq!(eval("64#Z").unwrap(), 61);
+        assert_eq!(eval("64#@").unwrap(), 62);
+        assert_eq!(eval("64#_").unwrap(), 63);
+        assert_eq!(eval("64#10 + 1").unwrap(), 65);
+        assert_eq!(eval("37#A").unwrap(), 36);
+        assert!(error("08").contains("value too great for base"));
+        assert!(error("2#102").contains("value too great for base"));
+        assert!(error("1#1").contains("invalid arithmetic base"));
+        assert!(error("65#1").contains("invalid arithmetic base"));
+        assert!(error("36#@").contains("value too great for base"));
+        assert!(error("1@").contains("invalid arithmetic operator"));
+    }
+
+    #[test]
+    fn syntax_errors() {
+        assert_eq!(eval("  ").unwrap(), 0);
+        assert!(error("1 +").contains("operand expected"));
+        assert!(error("(1").contains("`)' expected"));
+        assert!(error("1 2").contains("syntax error"));
+        assert!(error("3 = 4").contains("syntax error"));
+    }
+}
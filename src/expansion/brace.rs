@@ -0,0 +1,299 @@
+use crate::parser::{read_backquoted, read_until_closing, Word, WordPart};
+
+/// Nombre maximal de valeurs d'une séquence `{début..fin}` : au-delà, le mot
+/// reste tel quel plutôt que d'épuiser la mémoire
+const MAX_SEQUENCE_LENGTH: u64 = 1_000_000;
+
+/// Élément d'un mot vu par l'expansion des accolades
+#[derive(Debug, Clone)]
+enum Unit {
+    /// Caractère hors quotes, susceptible d'être une accolade ou une virgule
+    Char(char),
+    /// `${...}`, `$(...)` ou `` `...` `` hors quotes, jamais découpé
+    Opaque(String),
+    /// Fragment entre quotes, recopié tel quel
+    Quoted(WordPart),
+}
+
+impl Unit {
+    fn is_char(&self, expected: char) -> bool {
+        matches!(self, Self::Char(c) if *c == expected)
+    }
+}
+
+/// Développe les accolades d'un mot (`a{b,c}d`, `{1..10..2}`, `{a..e}`) en
+/// autant de mots, dans l'ordre ; un mot sans accolade valide est retourné
+/// tel quel. Les accolades entre quotes ou dans une expansion `$` restent
+/// littérales.
+pub fn expand(word: &Word) -> Vec<Word> {
+    expand_units(&units(word)).into_iter().map(into_word).collect()
+}
+
+fn units(word: &Word) -> Vec<Unit> {
+    let mut units = Vec::new();
+
+    for part in &word.parts {
+        let WordPart::Unquoted(text) = part else {
+            units.push(Unit::Quoted(part.clone()));
+            continue;
+        };
+
+        let mut chars = text.chars().peekable();
+        while let Some(ch) = chars.next() {
+            let opaque = match (ch, chars.peek()) {
+                ('$', Some(&open @ ('{' | '('))) => {
+                    chars.next();
+                    let close = if open == '{' { '}' } else { ')' };
+                    read_until_closing(&mut chars, open, close)
+                        .map(|content| format!("${}{}{}", open, content, close))
+                }
+                ('`', _) => read_backquoted(&mut chars).map(|content| format!("`{}`", content)),
+                _ => {
+                    units.push(Unit::Char(ch));
+                    continue;
+                }
+            };
+            // Le lexer garantit que ces constructions sont complètes
+            units.push(Unit::Opaque(opaque.unwrap_or_default()));
+        }
+    }
+
+    units
+}
+
+fn into_word(units: Vec<Unit>) -> Word {
+    let mut word = Word::default();
+    let mut text = String::new();
+
+    for unit in units {
+        match unit {
+            Unit::Char(c) => text.push(c),
+            Unit::Opaque(raw) => text.push_str(&raw),
+            Unit::Quoted(part) => {
+                if !text.is_empty() {
+                    word.push(WordPart::Unquoted(std::mem::take(&mut text)));
+                }
+                word.push(part);
+            }
+        }
+    }
+
+    if !text.is_empty() {
+        word.push(WordPart::Unquoted(text));
+    }
+    word
+}
+
+fn expand_units(units: &[Unit]) -> Vec<Vec<Unit>> {
+    for (open, unit) in units.iter().enumerate() {
+        if !unit.is_char('{') {
+            continue;
+        }
+        let Some(close) = matching_brace(units, open) else {
+            continue;
+        };
+        let Some(alternatives) = alternatives(&units[open + 1..close]) else {
+            continue;
+        };
+
+        let prefix = &units[..open];
+        let suffixes = expand_units(&units[close + 1..]);
+
+        let mut results = Vec::new();
+        for alternative in alternatives {
+            for suffix in &suffixes {
+                let mut result = prefix.to_vec();
+                result.extend(alternative.iter().cloned());
+                result.extend(suffix.iter().cloned());
+                results.push(result);
+            }
+        }
+        return results;
+    }
+
+    vec![units.to_vec()]
+}
+
+/// Position de l'accolade fermante associée à celle située en `open`
+fn matching_brace(units: &[Unit], open: usize) -> Option<usize> {
+    let mut depth = 0;
+    for (i, unit) in units.iter().enumerate().skip(open) {
+        if unit.is_char('{') {
+            depth += 1;
+        } else if unit.is_char('}') {
+            depth -= 1;
+            if depth == 0 {
+                return Some(i);
+            }
+        }
+    }
+    None
+}
+
+/// Mots produits par le contenu d'une paire d'accolades : liste `a,b,c`
+/// (chaque élément étant lui-même développé) ou séquence `x..y[..pas]`.
+/// `None` si le contenu ne forme ni l'un ni l'autre (`{}`, `{a}`).
+fn alternatives(content: &[Unit]) -> Option<Vec<Vec<Unit>>> {
+    let mut items = Vec::new();
+    let mut start = 0;
+    let mut depth = 0;
+
+    for (i, unit) in content.iter().enumerate() {
+        if unit.is_char('{') {
+            depth += 1;
+        } else if unit.is_char('}') {
+            depth -= 1;
+        } else if unit.is_char(',') && depth == 0 {
+            items.push(&content[start..i]);
+            start = i + 1;
+        }
+    }
+
+    if items.is_empty() {
+        return sequence(content);
+    }
+
+    items.push(&content[start..]);
+    Some(items.into_iter().flat_map(expand_units).collect())
+}
+
+/// Séquence numérique (`{1..10}`, `{01..12}`, `{10..1..3}`) ou de lettres
+/// (`{a..e}`)
+fn sequence(content: &[Unit]) -> Option<Vec<Vec<Unit>>> {
+    let text = content
+        .iter()
+        .map(|unit| match unit {
+            Unit::Char(c) => Some(*c),
+            _ => None,
+        })
+        .collect::<Option<String>>()?;
+
+    let bounds: Vec<&str> = text.split("..").collect();
+    let (start, end, step) = match bounds[..] {
+        [start, end] => (start, end, 1),
+        [start, end, step] => (start, end, step.parse::<i64>().ok()?.unsigned_abs().max(1)),
+        _ => return None,
+    };
+
+    let items: Vec<String> = match (start.parse::<i64>(), end.parse::<i64>()) {
+        (Ok(first), Ok(last)) => {
+            // Un zéro en tête impose une largeur fixe (`{01..12}`)
+            let padded = |bound: &str| bound.trim_start_matches('-').len() > 1
+                && bound.trim_start_matches('-').starts_with('0');
+            let width = if padded(start) || padded(end) {
+                start.len().max(end.len())
+            } else {
+                0
+            };
+            range(first, last, step)?
+                .map(|n| format!("{:0width$}", n, width = width))
+                .collect()
+        }
+        _ => {
+            let first = single_letter(start)?;
+            let last = single_letter(end)?;
+            range(first as i64, last as i64, step)?
+                .filter_map(|c| char::from_u32(c as u32))
+                .map(String::from)
+                .collect()
+        }
+    };
+
+    Some(
+        items
+            .into_iter()
+            .map(|item| item.chars().map(Unit::Char).collect())
+            .collect(),
+    )
+}
+
+fn single_letter(bound: &str) -> Option<char> {
+    let mut chars = bound.chars();
+    match (chars.next(), chars.next()) {
+        (Some(c), None) if c.is_ascii_alphabetic() => Some(c),
+        _ => None,
+    }
+}
+
+/// Valeurs de `first` à `last` inclus, croissantes ou décroissantes ;
+/// `None` s'il y en a plus de `MAX_SEQUENCE_LENGTH`
+fn range(first: i64, last: i64, step: u64) -> Option<impl Iterator<Item = i64>> {
+    let count = (first.abs_diff(last) / step)
+        .checked_add(1)
+        .filter(|&count| count <= MAX_SEQUENCE_LENGTH)?;
+    // Calculs sur 128 bits : `i * step` peut dépasser `i64` même si le
+    // résultat final reste entre `first` et `last`
+    let step = if first <= last { step as i128 } else { -(step as i128) };
+    Some((0..count).map(move |i| (first as i128 + i as i128 * step) as i64))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::Lexer;
+
+    fn expand_text(text: &str) -> Vec<String> {
+        expand(&Lexer::lex_word(text).unwrap())
+            .iter()
+            .map(Word::to_string)
+            .collect()
+    }
+
+    #[test]
+    fn lists() {
+        assert_eq!(expand_text("a{b,c}d"), ["abd", "acd"]);
+        assert_eq!(expand_text("{a,b}{1,2}"), ["a1", "a2", "b1", "b2"]);
+        assert_eq!(expand_text("{a,{b,c}}"), ["a", "b", "c"]);
+        assert_eq!(expand_text("x{,y}"), ["x", "xy"]);
+    }
+
+    #[test]
+    fn ranges() {
+        assert_eq!(expand_text("{1..3}"), ["1", "2", "3"]);
+        assert_eq!(expand_text("{3..1}"), ["3", "2", "1"]);
+        assert_eq!(expand_text("{-1..1}"), ["-1", "0", "1"]);
+        assert_eq!(expand_text("{1..10..4}"), ["1", "5", "9"]);
+        assert_eq!(expand_text("{10..1..-4}"), ["10", "6", "2"]);
+        assert_eq!(expand_text("{1..3..0}"), ["1", "2", "3"]);
+        assert_eq!(expand_text("{01..03}"), ["01", "02", "03"]);
+        assert_eq!(expand_text("{c..a}"), ["c", "b", "a"]);
+    }
+
+    #[test]
+    fn range_edges() {
+        assert_eq!(
+            expand_text("{9223372036854775806..9223372036854775807}"),
+            ["9223372036854775806", "9223372036854775807"]
+        );
+        assert_eq!(
+            expand_text("{9223372036854775807..-9223372036854775808..9223372036854775807}"),
+            ["9223372036854775807", "0", "-9223372036854775807"]
+        );
+        // Trop de valeurs pour les compter : le mot reste tel quel
+        assert_eq!(
+            expand_text("{-9223372036854775808..9223372036854775807}"),
+            ["{-9223372036854775808..9223372036854775807}"]
+        );
+        assert_eq!(expand_text("{1..99999999999999999999}"), ["{1..99999999999999999999}"]);
+    }
+
+    #[test]
+    fn oversized_ranges_are_left_alone() {
+        assert_eq!(expand_text("{1..9223372036854775807}"), ["{1..9223372036854775807}"]);
+        assert_eq!(expand_text("{0..1000000}"), ["{0..1000000}"]);
+        assert_eq!(expand_text("{a{1..1000001},b}"), ["a{1..1000001}", "b"]);
+        assert_eq!(
+            expand_text("{0..9000000000000000000..3000000000000000000}"),
+            ["0", "3000000000000000000", "6000000000000000000", "9000000000000000000"]
+        );
+    }
+
+    #[test]
+    fn invalid_braces_are_left_alone() {
+        assert_eq!(expand_text("{a}"), ["{a}"]);
+        assert_eq!(expand_text("{a,b"), ["{a,b"]);
+        assert_eq!(expand_text("{1..a}"), ["{1..a}"]);
+        assert_eq!(expand_text("\"{a,b}\""), ["\"{a,b}\""]);
+        assert_eq!(expand_text("${x,y}"), ["${x,y}"]);
+    }
+}
@@ -0,0 +1,49 @@
+use crate::execution::ProcessSubstitution;
+use crate::parser::Redirection;
+
+/// Commande simple après expansion, prête à être exécutée
+#[derive(Debug)]
+pub struct ExpandedCommand {
+    /// Affectations `NOM=valeur` précédant la commande
+    pub assignments: Vec<(String, String)>,
+    pub words: Vec<String>,
+    pub redirections: Vec<Redirection>,
+    /// Code de retour de la dernière substitution de commande effectuée ;
+    /// c'est celui d'une ligne sans nom de commande (`A=$(false)`)
+    pub substitution_status: Option<i32>,
+    /// Processus lancés par `<(cmd)` / `>(cmd)`, à attendre une fois la
+    /// commande lancée
+    pub substitutions: Vec<ProcessSubstitution>,
+}
+
+impl ExpandedCommand {
+    /// Nom de la commande, absent si la ligne ne contient que des redirections
+    pub fn name(&self) -> Option<&str> {
+        self.words.first().map(String::as_str)
+    }
+
+    pub fn args(&self) -> &[String] {
+        self.words.get(1..).unwrap_or(&[])
+    }
+
+    /// Commande telle qu'affichée par `-x`, les mots spéciaux entre quotes
+    pub fn trace(&self) -> String {
+        let assignments = self
+            .assignments
+            .iter()
+            .map(|(name, value)| format!("{}={}", name, quote(value)));
+        let words = self.words.iter().map(|word| quote(word));
+
+        assignments.chain(words).collect::<Vec<_>>().join(" ")
+    }
+}
+
+/// Entoure `word` de quotes simples s'il contient des caractères spéciaux
+/// pour le shell
+fn quote(word: &str) -> String {
+    let plain = |c: char| c.is_ascii_alphanumeric() || "_-./:=+,@%".contains(c);
+    if !word.is_empty() && word.chars().all(plain) {
+        return word.to_string();
+    }
+    format!("'{}'", word.replace('\'', "'\\''"))
+}
@@ -0,0 +1,580 @@
+use crate::expansion::arithmetic;
+use crate::expansion::brace;
+use crate::expansion::field::{Field, Fields, DEFAULT_IFS};
+use crate::expansion::glob;
+use crate::expansion::parameter::{Condition, ParameterExpansion, ReplaceMode};
+use crate::expansion::pattern::Pattern;
+use crate::expansion::tilde;
+use crate::execution::{ProcessSubstitution, SubstitutionKind};
+use crate::expansion::{ExpandedCommand, FatalExpansion};
+use crate::parser::{is_name, read_backquoted, read_until_closing, Lexer, ParsedCommand, Redirection, Word, WordPart};
+use crate::shell::Shell;
+use anyhow::{anyhow, bail, Result};
+
+/// Applique les expansions du shell aux mots d'une commande.
+///
+/// Les fragments entre quotes simples (ou échappés) sont recopiés tels
+/// quels ; les autres voient leurs `$` remplacés.
+pub struct Expander<'a> {
+    shell: &'a mut Shell,
+    substitution_status: Option<i32>,
+    substitutions: Vec<ProcessSubstitution>,
+}
+
+impl<'a> Expander<'a> {
+    pub fn new(shell: &'a mut Shell) -> Self {
+        Self {
+            shell,
+            substitution_status: None,
+            substitutions: Vec::new(),
+        }
+    }
+
+    /// Expanse les mots et les cibles de redirection d'une commande
+    pub fn expand_command(&mut self, command: &ParsedCommand) -> Result<ExpandedCommand> {
+        let assignments = command
+            .assignments
+            .iter()
+            .map(|assignment| {
+                let value = tilde::expand_assignment(&assignment.value, self.shell.variables());
+                Ok((assignment.name.clone(), self.expand_word(&value)?))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let words = self.expand_words(&command.words)?;
+
+        let redirections = self.expand_redirections(&command.redirections)?;
+
+        Ok(ExpandedCommand {
+            assignments,
+            words,
+            redirections,
+            substitution_status: self.substitution_status,
+            substitutions: std::mem::take(&mut self.substitutions),
+        })
+    }
+
+    /// Expanse les cibles de redirection, sans découpage ni globbing
+    pub fn expand_redirections(&mut self, redirections: &[Redirection<Word>]) -> Result<Vec<Redirection>> {
+        redirections
+            .iter()
+            .map(|redirection| {
+                redirection.try_map(|target| {
+                    let target = tilde::expand(target, self.shell.variables());
+                    self.expand_word(&target)
+                })
+            })
+            .collect()
+    }
+
+    /// Substitutions de processus lancées pendant l'expansion, que
+    /// l'appelant devra fermer et attendre
+    pub fn into_substitutions(self) -> Vec<ProcessSubstitution> {
+        self.substitutions
+    }
+
+    /// Développe les accolades et les `~` des mots d'une commande, les
+    /// expanse et découpe le résultat en champs, puis remplace ceux qui
+    /// contiennent des caractères de motif hors quotes par les chemins
+    /// correspondants
+    pub fn expand_words(&mut self, words: &[Word]) -> Result<Vec<String>> {
+        let mut expanded = Vec::with_capacity(words.len());
+        let ifs = self
+            .shell
+            .variables()
+            .get("IFS")
+            .unwrap_or_else(|| DEFAULT_IFS.to_string());
+
+        for word in words.iter().flat_map(brace::expand) {
+            let word = tilde::expand(&word, self.shell.variables());
+            let mut fields = Fields::new(Some(ifs.clone()));
+            self.expand_parts(&word, &mut fields)?;
+
+            for field in fields.finish() {
+                if Pattern::new(&field.pattern).as_literal().is_some() {
+                    expanded.push(field.text);
+                    continue;
+                }
+
+                let paths = glob::expand(&field.pattern, self.shell.options());
+                if !paths.is_empty() {
+                    expanded.extend(paths);
+                } else if self.shell.options().failglob {
+                    bail!("no match: {}", field.text);
+                } else if !self.shell.options().nullglob {
+                    expanded.push(field.text);
+                }
+            }
+        }
+
+        Ok(expanded)
+    }
+
+    /// Expanse un mot en une seule chaîne, sans découpage ni globbing
+    pub fn expand_word(&mut self, word: &Word) -> Result<String> {
+        Ok(self.expand_unsplit(word)?.text)
+    }
+
+    fn expand_unsplit(&mut self, word: &Word) -> Result<Field> {
+        let mut fields = Fields::new(None);
+        self.expand_parts(word, &mut fields)?;
+        Ok(fields.into_single())
+    }
+
+    fn expand_parts(&mut self, word: &Word, fields: &mut Fields) -> Result<()> {
+        for part in &word.parts {
+            match part {
+                WordPart::SingleQuoted(text) => fields.push_literal(text, true),
+                // `""` produit un champ vide, contrairement à `"$@"` sans paramètres
+                WordPart::DoubleQuoted(text) if text.is_empty() => fields.push_literal("", true),
+                WordPart::DoubleQuoted(text) => self.expand_text(text, true, fields)?,
+                WordPart::Unquoted(text) => self.expand_text(text, false, fields)?,
+            }
+        }
+        Ok(())
+    }
+
+    /// Remplace les références de variables (`$NOM`, `${...}`, `$?`), les
+    /// substitutions de commande (`$(...)`, `` `...` ``) et de processus
+    /// (`<(...)`, `>(...)`) et les expressions arithmétiques (`$((...))`)
+    /// présentes dans `text` ; un `$` isolé reste littéral. `quoted` indique
+    /// si `text` était entre quotes doubles.
+    fn expand_text(&mut self, text: &str, quoted: bool, fields: &mut Fields) -> Result<()> {
+        let mut chars = text.chars().peekable();
+
+        while let Some(ch) = chars.next() {
+            let value = match (ch, chars.peek()) {
+                ('$', Some(&c @ ('@' | '*'))) => {
+                    chars.next();
+                    self.expand_positional(c, quoted, fields);
+                    continue;
+                }
+                ('$', Some(&c)) if c.is_ascii_digit() || "?$!#-".contains(c) => {
+                    chars.next();
+                    self.lookup(&c.to_string()).unwrap_or_default()
+                }
+                ('$', Some('{')) => {
+                    chars.next();
+                    let content = read_until_closing(&mut chars, '{', '}')
+                        .ok_or_else(|| anyhow!("${{{}: bad substitution", text))?;
+                    match ParameterExpansion::parse(&content)? {
+                        ParameterExpansion::Value(name) if name == "@" || name == "*" => {
+                            self.expand_positional(if name == "@" { '@' } else { '*' }, quoted, fields);
+                            continue;
+                        }
+                        expansion => {
+                            self.expand_parameter(&expansion, quoted, fields)?;
+                            continue;
+                        }
+                    }
+                }
+                ('$', Some('(')) => {
+                    chars.next();
+                    let content = read_until_closing(&mut chars, '(', ')')
+                        .ok_or_else(|| anyhow!("unexpected EOF while looking for matching `)'"))?;
+                    match Self::arithmetic_expression(&content) {
+                        Some(expression) => self.expand_arithmetic(expression)?.to_string(),
+                        None => self.substitute(&content)?,
+                    }
+                }
+                ('<' | '>', Some('(')) if !quoted => {
+                    chars.next();
+                    let script = read_until_closing(&mut chars, '(', ')')
+                        .ok_or_else(|| anyhow!("unexpected EOF while looking for matching `)'"))?;
+                    let kind = if ch == '<' {
+                        SubstitutionKind::Input
+                    } else {
+                        SubstitutionKind::Output
+                    };
+                    let substitution =
+                        self.shell
+                            .spawn_process_substitution(&script, kind, &self.substitutions)?;
+                    let path = substitution.path();
+                    self.substitutions.push(substitution);
+                    path
+                }
+                ('`', _) => {
+                    let script = read_backquoted(&mut chars)
+                        .ok_or_else(|| anyhow!("unexpected EOF while looking for matching ``'"))?;
+                    self.substitute(&Self::unescape_backquoted(&script))?
+                }
+                ('$', Some(&c)) if c.is_ascii_alphabetic() || c == '_' => {
+                    let mut name = String::new();
+                    while let Some(&c) = chars.peek() {
+                        if !(c.is_ascii_alphanumeric() || c == '_') {
+                            break;
+                        }
+                        name.push(c);
+                        chars.next();
+                    }
+                    self.lookup(&name).unwrap_or_default()
+                }
+                (ch, _) => {
+                    fields.push_literal(&ch.to_string(), quoted);
+                    continue;
+                }
+            };
+
+            fields.push_expansion(&value, quoted);
+        }
+
+        Ok(())
+    }
+
+    /// `$@` et `$*` : hors quotes, chaque paramètre est découpé à son tour ;
+    /// `"$@"` donne un champ par paramètre (aucun s'il n'y en a pas) et
+    /// `"$*"` un seul champ, les paramètres joints par le premier caractère
+    /// de `IFS`.
+    fn expand_positional(&mut self, kind: char, quoted: bool, fields: &mut Fields) {
+        let params = self.shell.positional_params().to_vec();
+
+        if kind == '*' && quoted {
+            fields.push_expansion(&params.join(&self.ifs_separator()), true);
+            return;
+        }
+
+        for (index, param) in params.iter().enumerate() {
+            if index > 0 {
+                fields.break_field(quoted);
+            }
+            fields.push_expansion(param, quoted);
+        }
+    }
+
+    /// Séparateur de `$*` : premier caractère de `IFS`, espace s'il n'est pas défini
+    fn ifs_separator(&self) -> String {
+        match self.shell.variables().get("IFS") {
+            Some(ifs) => ifs.chars().next().map(String::from).unwrap_or_default(),
+            None => " ".to_string(),
+        }
+    }
+
+    /// Reconnaît `$((expression))` à partir du contenu de `$(...)` : il doit
+    /// être lui-même entièrement entre parenthèses, sinon il s'agit d'une
+    /// commande (`$((a) | b)`).
+    fn arithmetic_expression(content: &str) -> Option<&str> {
+        let expression = content.strip_prefix('(')?.strip_suffix(')')?;
+
+        let mut depth = 0usize;
+        for c in expression.chars() {
+            match c {
+                '(' => depth += 1,
+                ')' => depth = depth.checked_sub(1)?,
+                _ => {}
+            }
+        }
+        (depth == 0).then_some(expression)
+    }
+
+    /// Évalue `$((...))` après expansion des paramètres et substitutions
+    /// qu'elle contient
+    fn expand_arithmetic(&mut self, expression: &str) -> Result<i64> {
+        let mut fields = Fields::new(None);
+        self.expand_text(expression, true, &mut fields)?;
+        arithmetic::evaluate(&fields.into_single().text, self.shell.variables_mut())
+            .map_err(|err| FatalExpansion::new(err).into())
+    }
+
+    /// Exécute une substitution de commande et mémorise son code de retour
+    fn substitute(&mut self, script: &str) -> Result<String> {
+        let output = self.shell.capture_output(script)?;
+        self.substitution_status = Some(self.shell.last_status());
+        Ok(output)
+    }
+
+    /// Dans `` `...` ``, `\` ne protège que `$`, `` ` `` et `\` : il est
+    /// retiré devant eux avant d'exécuter la commande.
+    fn unescape_backquoted(script: &str) -> String {
+        let mut result = String::with_capacity(script.len());
+        let mut chars = script.chars().peekable();
+
+        while let Some(ch) = chars.next() {
+            match (ch, chars.peek()) {
+                ('\\', Some(&next @ ('$' | '`' | '\\'))) => {
+                    chars.next();
+                    result.push(next);
+                }
+                (ch, _) => result.push(ch),
+            }
+        }
+
+        result
+    }
+
+    /// Évalue une expansion `${...}` et ajoute son résultat aux champs.
+    /// L'opérande de `${NOM:-mot}` et `${NOM:+mot}` y est expansé tel quel :
+    /// ses parties entre quotes ne sont ni découpées ni globbées, et
+    /// `"$@"` y garde un champ par paramètre.
+    fn expand_parameter(
+        &mut self,
+        expansion: &ParameterExpansion,
+        quoted: bool,
+        fields: &mut Fields,
+    ) -> Result<()> {
+        let value = match expansion {
+            ParameterExpansion::Value(name) => self.lookup(name).unwrap_or_default(),
+            // `${#@}` et `${#*}` comptent les paramètres positionnels
+            ParameterExpansion::Length(name) if name == "@" || name == "*" => {
+                self.shell.positional_params().len().to_string()
+            }
+            ParameterExpansion::Length(name) => {
+                self.lookup(name).unwrap_or_default().chars().count().to_string()
+            }
+            ParameterExpansion::Conditional {
+                name,
+                condition,
+                null_is_unset,
+                word,
+            } => {
+                let value = self.lookup(name);
+                let is_set = value.as_ref().is_some_and(|v| !(*null_is_unset && v.is_empty()));
+
+                match (condition, is_set) {
+                    (Condition::UseAlternative, true) | (Condition::UseDefault, false) => {
+                        return self.expand_operand_into(word, quoted, fields);
+                    }
+                    (Condition::UseAlternative, false) => String::new(),
+                    (_, true) => value.unwrap_or_default(),
+                    (Condition::AssignDefault, false) => {
+                        if !is_name(name) {
+                            bail!("${}: cannot assign in this way", name);
+                        }
+                        let value = self.expand_operand(word)?;
+                        self.shell.variables_mut().set(name, &value);
+                        value
+                    }
+                    (Condition::ErrorIfUnset, false) => {
+                        let message = self.expand_operand(word)?;
+                        if message.is_empty() {
+                            return Err(FatalExpansion::new(format!("{}: parameter null or not set", name)).into());
+                        }
+                        return Err(FatalExpansion::new(format!("{}: {}", name, message)).into());
+                    }
+                }
+            }
+            ParameterExpansion::RemovePrefix {
+                name,
+                pattern,
+                longest,
+            } => {
+                let value = self.lookup(name).unwrap_or_default();
+                let pattern = self.expand_pattern(pattern)?;
+                let mut ends = Self::char_boundaries(&value);
+                if *longest {
+                    ends.reverse();
+                }
+                ends
+                    .into_iter()
+                    .find(|&end| pattern.matches(&value[..end]))
+                    .map_or(value.clone(), |end| value[end..].to_string())
+            }
+            ParameterExpansion::RemoveSuffix {
+                name,
+                pattern,
+                longest,
+            } => {
+                let value = self.lookup(name).unwrap_or_default();
+                let pattern = self.expand_pattern(pattern)?;
+                let mut starts = Self::char_boundaries(&value);
+                if !*longest {
+                    starts.reverse();
+                }
+                starts
+                    .into_iter()
+                    .find(|&start| pattern.matches(&value[start..]))
+                    .map_or(value.clone(), |start| value[..start].to_string())
+            }
+            ParameterExpansion::Replace {
+                name,
+                pattern,
+                replacement,
+                mode,
+            } => {
+                let value = self.lookup(name).unwrap_or_default();
+                let pattern = self.expand_pattern(pattern)?;
+                let replacement = self.expand_operand(replacement)?;
+                Self::replace(&value, &pattern, &replacement, *mode)
+            }
+        };
+
+        fields.push_expansion(&value, quoted);
+        Ok(())
+    }
+
+    /// Remplace les correspondances de `pattern` dans `value`, la plus
+    /// longue à chaque position
+    fn replace(value: &str, pattern: &Pattern, replacement: &str, mode: ReplaceMode) -> String {
+        let boundaries = Self::char_boundaries(value);
+        let longest_match_at = |start: usize| {
+            boundaries
+                .iter()
+                .rev()
+                .copied()
+                .filter(|&end| end >= start)
+                .find(|&end| pattern.matches(&value[start..end]))
+        };
+
+        match mode {
+            ReplaceMode::Prefix => match longest_match_at(0) {
+                Some(end) => format!("{}{}", replacement, &value[end..]),
+                None => value.to_string(),
+            },
+            ReplaceMode::Suffix => match boundaries
+                .iter()
+                .copied()
+                .find(|&start| pattern.matches(&value[start..]))
+            {
+                Some(start) => format!("{}{}", &value[..start], replacement),
+                None => value.to_string(),
+            },
+            ReplaceMode::First | ReplaceMode::All => {
+                let mut result = String::with_capacity(value.len());
+                let mut position = 0;
+                let mut replaced = false;
+
+                for &start in &boundaries {
+                    if start < position {
+                        continue;
+                    }
+                    if replaced && mode == ReplaceMode::First {
+                        break;
+                    }
+                    // Une correspondance vide ne remplace rien
+                    if let Some(end) = longest_match_at(start).filter(|&end| end > start) {
+                        result.push_str(&value[position..start]);
+                        result.push_str(replacement);
+                        position = end;
+                        replaced = true;
+                    }
+                }
+
+                result.push_str(&value[position..]);
+                result
+            }
+        }
+    }
+
+    /// Positions de début de caractère de `text`, fin comprise
+    fn char_boundaries(text: &str) -> Vec<usize> {
+        text.char_indices()
+            .map(|(i, _)| i)
+            .chain(std::iter::once(text.len()))
+            .collect()
+    }
+
+    /// Expanse l'opérande d'un `${NOM:-mot}`, quotes comprises
+    fn expand_operand(&mut self, raw: &str) -> Result<String> {
+        let word = tilde::expand(&Lexer::lex_word(raw)?, self.shell.variables());
+        self.expand_word(&word)
+    }
+
+    /// Expanse l'opérande d'un `${NOM:-mot}` directement dans les champs du
+    /// mot. Entre quotes doubles, tout l'opérande est protégé ; sinon seules
+    /// ses parties hors quotes, texte écrit compris, sont découpées et
+    /// globbées.
+    fn expand_operand_into(&mut self, raw: &str, quoted: bool, fields: &mut Fields) -> Result<()> {
+        let word = tilde::expand(&Lexer::lex_word(raw)?, self.shell.variables());
+        if quoted {
+            // `"${NOM:-}"` produit tout de même un champ vide
+            fields.push_literal("", true);
+        }
+
+        for part in &word.parts {
+            match part {
+                WordPart::SingleQuoted(text) => fields.push_literal(text, true),
+                WordPart::DoubleQuoted(text) if text.is_empty() => fields.push_literal("", true),
+                WordPart::DoubleQuoted(text) => self.expand_text(text, true, fields)?,
+                WordPart::Unquoted(text) if quoted => self.expand_text(text, true, fields)?,
+                WordPart::Unquoted(text) => {
+                    let mut unsplit = Fields::new(None);
+                    self.expand_text(text, false, &mut unsplit)?;
+                    fields.push_expansion(&unsplit.into_single().text, false);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Expanse un motif : les parties entre quotes y perdent leur sens spécial
+    fn expand_pattern(&mut self, raw: &str) -> Result<Pattern> {
+        let word = Lexer::lex_word(raw)?;
+        Ok(Pattern::new(&self.expand_unsplit(&word)?.pattern))
+    }
+
+    /// Valeur d'un paramètre (variable, paramètre positionnel ou spécial),
+    /// `None` s'il n'est pas défini
+    fn lookup(&self, name: &str) -> Option<String> {
+        let shell = &self.shell;
+        match name {
+            "?" => Some(shell.last_status().to_string()),
+            "$" => Some(shell.pid().to_string()),
+            "!" => shell.last_background_pid().map(|pid| pid.to_string()),
+            "#" => Some(shell.positional_params().len().to_string()),
+            "@" => Some(shell.positional_params().join(" ")),
+            "*" => Some(shell.positional_params().join(&self.ifs_separator())),
+            "-" => Some(shell.option_flags()),
+            "_" => Some(shell.last_argument().to_string()),
+            "0" => Some(shell.script_name().to_string()),
+            _ if name.starts_with(|c: char| c.is_ascii_digit()) => {
+                // `${00}` désigne aussi `$0`
+                match name.parse::<usize>().ok()? {
+                    0 => Some(shell.script_name().to_string()),
+                    index => shell.positional_params().get(index - 1).cloned(),
+                }
+            }
+            _ => shell.variables().get(name),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::Lexer;
+
+    fn expand(shell: &mut Shell, text: &str) -> Vec<String> {
+        let word = Lexer::lex_word(text).unwrap();
+        Expander::new(shell).expand_words(&[word]).unwrap()
+    }
+
+    #[test]
+    fn operands_keep_their_quoting() {
+        let mut shell = Shell::new();
+        shell.variables_mut().set("EXPANDER_TEST_SET", "value");
+
+        assert_eq!(expand(&mut shell, "${EXPANDER_TEST_UNSET:-\"a b\" c  d}"), ["a b", "c", "d"]);
+        assert_eq!(expand(&mut shell, "\"${EXPANDER_TEST_UNSET:-a  b}\""), ["a  b"]);
+        assert_eq!(expand(&mut shell, "${EXPANDER_TEST_SET:+x y}"), ["x", "y"]);
+        assert_eq!(expand(&mut shell, "${EXPANDER_TEST_SET:+'x y'}z"), ["x yz"]);
+        assert_eq!(expand(&mut shell, "${EXPANDER_TEST_UNSET:-\"\"}"), [""]);
+        assert_eq!(expand(&mut shell, "${EXPANDER_TEST_UNSET:-}"), Vec::<String>::new());
+    }
+
+    #[test]
+    fn unmatched_patterns() {
+        let dir = std::env::temp_dir().join(format!("shell-expander-glob-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("file.txt"), "").unwrap();
+        let dir = dir.to_string_lossy().into_owned();
+        let expand_glob = |shell: &mut Shell, pattern: &str| {
+            let word = Lexer::lex_word(&format!("{}/{}", dir, pattern)).unwrap();
+            Expander::new(shell).expand_words(&[word])
+        };
+
+        let mut shell = Shell::new();
+        assert_eq!(expand_glob(&mut shell, "*.txt").unwrap(), [format!("{}/file.txt", dir)]);
+        assert_eq!(expand_glob(&mut shell, "*.md").unwrap(), [format!("{}/*.md", dir)]);
+        assert_eq!(expand_glob(&mut shell, "'*'.txt").unwrap(), [format!("{}/*.txt", dir)]);
+
+        shell.options_mut().nullglob = true;
+        assert!(expand_glob(&mut shell, "*.md").unwrap().is_empty());
+        assert_eq!(expand_glob(&mut shell, "*.txt").unwrap(), [format!("{}/file.txt", dir)]);
+
+        // `failglob` l'emporte sur `nullglob`
+        shell.options_mut().failglob = true;
+        let error = expand_glob(&mut shell, "*.md").unwrap_err();
+        assert_eq!(error.to_string(), format!("no match: {}/*.md", dir));
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
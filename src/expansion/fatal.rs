@@ -0,0 +1,25 @@
+use std::fmt;
+
+/// Erreur d'expansion qui interrompt la ligne de commande en cours (`${x:?}`,
+/// expression arithmétique invalide) : un shell non interactif se termine,
+/// un shell interactif revient à l'invite.
+#[derive(Debug)]
+pub struct FatalExpansion {
+    message: String,
+}
+
+impl FatalExpansion {
+    pub fn new(message: impl fmt::Display) -> Self {
+        Self {
+            message: message.to_string(),
+        }
+    }
+}
+
+impl fmt::Display for FatalExpansion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+impl std::error::Error for FatalExpansion {}
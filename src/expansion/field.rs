@@ -0,0 +1,229 @@
+use crate::expansion::pattern;
+
+/// Valeur de `IFS` lorsque la variable n'est pas définie
+pub const DEFAULT_IFS: &str = " \t\n";
+
+/// Champ produit par l'expansion d'un mot, avant globbing
+#[derive(Debug, Clone, Default)]
+pub struct Field {
+    /// Texte final, quotes retirées
+    pub text: String,
+    /// Même texte où les caractères issus d'un contexte entre quotes sont
+    /// échappés, pour ne pas être interprétés comme motif
+    pub pattern: String,
+    /// Le champ contient du texte écrit ou entre quotes : il est conservé
+    /// même vide (`""`), contrairement à une expansion vide hors quotes
+    keep: bool,
+}
+
+impl Field {
+    fn push(&mut self, c: char, quoted: bool) {
+        self.text.push(c);
+        if quoted {
+            self.pattern.push_str(&pattern::escape(&c.to_string()));
+        } else {
+            self.pattern.push(c);
+        }
+    }
+}
+
+/// Assemble les champs d'un mot au fil de son expansion.
+///
+/// Le texte écrit dans le mot et les expansions entre quotes sont ajoutés
+/// tels quels au champ courant ; les résultats d'expansions hors quotes sont
+/// découpés sur les caractères de `IFS`. Les blancs de `IFS` (espace,
+/// tabulation, retour à la ligne) se regroupent et sont ignorés en début et
+/// fin de résultat ; chaque autre caractère de `IFS` délimite un champ, même
+/// vide.
+pub struct Fields {
+    /// `None` : aucun découpage (affectations, redirections...)
+    ifs: Option<String>,
+    fields: Vec<Field>,
+    /// `None` juste après un délimiteur : le prochain caractère ouvre un champ
+    current: Option<Field>,
+}
+
+impl Fields {
+    pub fn new(ifs: Option<String>) -> Self {
+        Self {
+            ifs,
+            fields: Vec::new(),
+            current: Some(Field::default()),
+        }
+    }
+
+    fn current(&mut self) -> &mut Field {
+        self.current.get_or_insert_with(Field::default)
+    }
+
+    /// Texte écrit dans le mot, jamais découpé
+    pub fn push_literal(&mut self, text: &str, quoted: bool) {
+        let field = self.current();
+        field.keep = true;
+        for c in text.chars() {
+            field.push(c, quoted);
+        }
+    }
+
+    /// Résultat d'une expansion ; découpé sur `IFS` s'il est hors quotes
+    pub fn push_expansion(&mut self, text: &str, quoted: bool) {
+        let Some(ifs) = self.ifs.clone().filter(|_| !quoted) else {
+            self.push_literal(text, quoted);
+            return;
+        };
+
+        let is_ifs_white = |c: char| ifs.contains(c) && c.is_ascii_whitespace();
+        let mut chars = text.chars().peekable();
+
+        while let Some(c) = chars.next() {
+            if !ifs.contains(c) {
+                self.current().push(c, false);
+                continue;
+            }
+
+            // Un délimiteur : blancs de `IFS` autour d'au plus un autre caractère de `IFS`
+            let mut explicit = !is_ifs_white(c);
+            while let Some(&next) = chars.peek() {
+                if is_ifs_white(next) {
+                    chars.next();
+                } else if ifs.contains(next) && !explicit {
+                    explicit = true;
+                    chars.next();
+                } else {
+                    break;
+                }
+            }
+
+            self.end_field(explicit);
+        }
+    }
+
+    /// Sépare deux paramètres positionnels de `$@` : un champ se termine
+    /// (conservé même vide entre quotes), ou un espace les sépare quand il
+    /// n'y a pas de découpage
+    pub fn break_field(&mut self, quoted: bool) {
+        if self.ifs.is_none() {
+            self.current().push(' ', quoted);
+        } else {
+            self.end_field(quoted);
+        }
+    }
+
+    /// Termine le champ courant ; `force` le conserve même vide
+    fn end_field(&mut self, force: bool) {
+        match self.current.take() {
+            Some(field) if force || field.keep || !field.text.is_empty() => self.fields.push(field),
+            Some(_) => {}
+            None if force => self.fields.push(Field::default()),
+            None => {}
+        }
+    }
+
+    pub fn finish(mut self) -> Vec<Field> {
+        self.end_field(false);
+        self.fields
+    }
+
+    /// Champ unique d'une expansion sans découpage
+    pub fn into_single(self) -> Field {
+        self.finish().into_iter().next().unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn texts(fields: Fields) -> Vec<String> {
+        fields.finish().into_iter().map(|field| field.text).collect()
+    }
+
+    /// Champs issus d'une seule expansion hors quotes
+    fn split(ifs: Option<&str>, text: &str) -> Vec<String> {
+        let mut fields = Fields::new(ifs.map(str::to_string));
+        fields.push_expansion(text, false);
+        texts(fields)
+    }
+
+    #[test]
+    fn whitespace_ifs() {
+        assert_eq!(split(Some(DEFAULT_IFS), "  a  b\tc\n"), ["a", "b", "c"]);
+        assert_eq!(split(Some(DEFAULT_IFS), "   "), Vec::<String>::new());
+        assert_eq!(split(Some(DEFAULT_IFS), ""), Vec::<String>::new());
+        assert_eq!(split(Some(" "), "a\tb c"), ["a\tb", "c"]);
+    }
+
+    #[test]
+    fn explicit_delimiters() {
+        assert_eq!(split(Some(":"), "a::b:"), ["a", "", "b"]);
+        assert_eq!(split(Some(":"), ":a"), ["", "a"]);
+        assert_eq!(split(Some(" :"), " a : b :: c "), ["a", "b", "", "c"]);
+    }
+
+    #[test]
+    fn no_splitting() {
+        assert_eq!(split(None, " a b "), [" a b "]);
+        assert_eq!(split(Some(""), " a b "), [" a b "]);
+
+        let mut fields = Fields::new(Some(DEFAULT_IFS.to_string()));
+        fields.push_expansion(" a  b ", true);
+        assert_eq!(texts(fields), [" a  b "]);
+    }
+
+    #[test]
+    fn literal_text_joins_split_results() {
+        let mut fields = Fields::new(Some(DEFAULT_IFS.to_string()));
+        fields.push_literal("x", false);
+        fields.push_expansion("a b", false);
+        fields.push_literal("y", false);
+        assert_eq!(texts(fields), ["xa", "by"]);
+
+        // Un texte quoté garde son espace même accolé à une expansion
+        let mut fields = Fields::new(Some(DEFAULT_IFS.to_string()));
+        fields.push_literal("a b", true);
+        fields.push_expansion("c d", false);
+        assert_eq!(texts(fields), ["a bc", "d"]);
+    }
+
+    #[test]
+    fn empty_fields() {
+        // `""` et `"$vide"` sont conservés, `$vide` disparaît
+        let mut fields = Fields::new(Some(DEFAULT_IFS.to_string()));
+        fields.push_literal("", true);
+        assert_eq!(texts(fields), [""]);
+
+        let mut fields = Fields::new(Some(DEFAULT_IFS.to_string()));
+        fields.push_expansion("", true);
+        assert_eq!(texts(fields), [""]);
+
+        let mut fields = Fields::new(Some(DEFAULT_IFS.to_string()));
+        fields.push_expansion("", false);
+        assert_eq!(texts(fields), Vec::<String>::new());
+    }
+
+    #[test]
+    fn positional_breaks() {
+        let mut fields = Fields::new(Some(DEFAULT_IFS.to_string()));
+        fields.push_literal("", true);
+        fields.push_expansion("a b", true);
+        fields.break_field(true);
+        fields.push_expansion("", true);
+        assert_eq!(texts(fields), ["a b", ""]);
+
+        let mut fields = Fields::new(None);
+        fields.push_expansion("a", true);
+        fields.break_field(true);
+        fields.push_expansion("b", true);
+        assert_eq!(fields.into_single().text, "a b");
+    }
+
+    #[test]
+    fn quoted_text_is_escaped_in_pattern() {
+        let mut fields = Fields::new(Some(DEFAULT_IFS.to_string()));
+        fields.push_literal("*", true);
+        fields.push_expansion("?", false);
+        let field = fields.into_single();
+        assert_eq!(field.text, "*?");
+        assert_eq!(field.pattern, "\\*?");
+    }
+}
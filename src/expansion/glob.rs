@@ -0,0 +1,216 @@
+use crate::expansion::pattern::Pattern;
+use crate::options::ShellOptions;
+use std::collections::HashSet;
+use std::fs;
+use std::os::unix::fs::MetadataExt;
+use std::path::Path;
+
+/// Chemins correspondant à `pattern`, triés ; vide si aucun ne correspond.
+///
+/// Le motif est découpé sur les `/` : chaque composant est comparé aux
+/// entrées du dossier atteint par les composants précédents. Un composant
+/// `**` correspond à zéro ou plusieurs niveaux de dossiers. Les fichiers
+/// cachés ne correspondent que si le composant commence par un `.` explicite
+/// ou si l'option `dotglob` est active.
+pub fn expand(pattern: &str, options: &ShellOptions) -> Vec<String> {
+    let (mut paths, relative) = match pattern.strip_prefix('/') {
+        Some(rest) => (vec!["/".to_string()], rest),
+        None => (vec![String::new()], pattern),
+    };
+    // `*/` ne retient que les dossiers
+    let directories_only = relative.ends_with('/');
+
+    let components: Vec<&str> = relative.split('/').filter(|c| !c.is_empty()).collect();
+    for (index, component) in components.iter().enumerate() {
+        let compiled = Pattern::new(component);
+        let is_last = index + 1 == components.len();
+
+        paths = if *component == "**" {
+            let walker = Walker {
+                include_hidden: options.dotglob,
+                follow_links: options.followlinks,
+                // En dernière position, `**` retient aussi les fichiers
+                directories_only: !is_last || directories_only,
+            };
+            paths.into_iter().flat_map(|path| walker.walk(&path)).collect()
+        } else if let Some(name) = compiled.as_literal() {
+            paths
+                .into_iter()
+                .map(|path| join(&path, &name))
+                .filter(|path| fs::symlink_metadata(path).is_ok())
+                .collect()
+        } else {
+            let include_hidden =
+                options.dotglob || component.starts_with('.') || component.starts_with("\\.");
+            paths
+                .into_iter()
+                .flat_map(|path| matching_entries(&path, &compiled, include_hidden))
+                .collect()
+        };
+
+        if paths.is_empty() {
+            break;
+        }
+    }
+
+    if directories_only {
+        paths = paths
+            .into_iter()
+            .filter(|path| Path::new(path).is_dir())
+            .map(|path| format!("{}/", path.trim_end_matches('/')))
+            .collect();
+    }
+
+    paths.retain(|path| !path.is_empty());
+    paths.sort();
+    paths.dedup();
+    paths
+}
+
+/// Entrées de `dir` dont le nom correspond à `pattern`
+fn matching_entries(dir: &str, pattern: &Pattern, include_hidden: bool) -> Vec<String> {
+    let Ok(entries) = fs::read_dir(if dir.is_empty() { "." } else { dir }) else {
+        return Vec::new();
+    };
+
+    entries
+        .filter_map(|entry| entry.ok()?.file_name().into_string().ok())
+        .filter(|name| include_hidden || !name.starts_with('.'))
+        .filter(|name| pattern.matches(name))
+        .map(|name| join(dir, &name))
+        .collect()
+}
+
+/// Parcours récursif d'une arborescence pour `**`
+struct Walker {
+    include_hidden: bool,
+    follow_links: bool,
+    directories_only: bool,
+}
+
+impl Walker {
+    /// `dir` lui-même puis tout ce qu'il contient, récursivement
+    fn walk(&self, dir: &str) -> Vec<String> {
+        let mut paths = vec![dir.to_string()];
+        let mut visited = HashSet::new();
+        if let Ok(metadata) = fs::metadata(if dir.is_empty() { "." } else { dir }) {
+            visited.insert((metadata.dev(), metadata.ino()));
+        }
+
+        self.walk_into(dir, &mut visited, &mut paths);
+        paths
+    }
+
+    /// `visited` contient les dossiers (périphérique, inode) déjà parcourus :
+    /// un lien symbolique qui remonte dans l'arborescence n'est pas suivi
+    fn walk_into(&self, dir: &str, visited: &mut HashSet<(u64, u64)>, paths: &mut Vec<String>) {
+        let Ok(entries) = fs::read_dir(if dir.is_empty() { "." } else { dir }) else {
+            return;
+        };
+
+        let mut names: Vec<String> = entries
+            .filter_map(|entry| entry.ok()?.file_name().into_string().ok())
+            .filter(|name| self.include_hidden || !name.starts_with('.'))
+            .collect();
+        names.sort();
+
+        for name in names {
+            let path = join(dir, &name);
+            let Ok(metadata) = fs::symlink_metadata(&path) else {
+                continue;
+            };
+
+            let directory = if metadata.file_type().is_symlink() {
+                if self.follow_links {
+                    fs::metadata(&path).ok().filter(|target| target.is_dir())
+                } else {
+                    None
+                }
+            } else {
+                Some(metadata).filter(|metadata| metadata.is_dir())
+            };
+
+            match directory {
+                Some(directory) => {
+                    paths.push(path.clone());
+                    if visited.insert((directory.dev(), directory.ino())) {
+                        self.walk_into(&path, visited, paths);
+                    }
+                }
+                None if !self.directories_only => paths.push(path),
+                None => {}
+            }
+        }
+    }
+}
+
+fn join(dir: &str, name: &str) -> String {
+    if dir.is_empty() {
+        name.to_string()
+    } else if dir.ends_with('/') {
+        format!("{}{}", dir, name)
+    } else {
+        format!("{}/{}", dir, name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Arborescence de test propre à `name`, recréée à chaque appel
+    fn tree(name: &str) -> String {
+        let root = std::env::temp_dir().join(format!("shell-glob-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        for dir in ["sub", ".config"] {
+            fs::create_dir_all(root.join(dir)).unwrap();
+        }
+        for file in ["a.txt", "b.txt", "c.rs", ".hidden.txt", "sub/d.txt", "sub/.e.txt"] {
+            fs::write(root.join(file), "").unwrap();
+        }
+        root.to_string_lossy().into_owned()
+    }
+
+    /// Correspondances de `pattern` sous `root`, relatives à `root`
+    fn glob(root: &str, pattern: &str, options: &ShellOptions) -> Vec<String> {
+        expand(&format!("{}/{}", root, pattern), options)
+            .into_iter()
+            .map(|path| path[root.len() + 1..].to_string())
+            .collect()
+    }
+
+    #[test]
+    fn components() {
+        let root = tree("components");
+        let options = ShellOptions::default();
+
+        assert_eq!(glob(&root, "*.txt", &options), ["a.txt", "b.txt"]);
+        assert_eq!(glob(&root, "?.rs", &options), ["c.rs"]);
+        assert_eq!(glob(&root, "[ab].*", &options), ["a.txt", "b.txt"]);
+        assert_eq!(glob(&root, "*/*.txt", &options), ["sub/d.txt"]);
+        assert_eq!(glob(&root, "*/", &options), ["sub/"]);
+        assert_eq!(glob(&root, "sub/d.txt", &options), ["sub/d.txt"]);
+        assert!(glob(&root, "*.md", &options).is_empty());
+        assert!(glob(&root, "nothing/*", &options).is_empty());
+
+        // Un caractère échappé ne joue pas le rôle de joker
+        assert!(glob(&root, "\\*.txt", &options).is_empty());
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn hidden_files() {
+        let root = tree("hidden");
+        let mut options = ShellOptions::default();
+
+        assert_eq!(glob(&root, "*", &options), ["a.txt", "b.txt", "c.rs", "sub"]);
+        assert_eq!(glob(&root, ".*.txt", &options), [".hidden.txt"]);
+        assert_eq!(glob(&root, "sub/.*", &options), ["sub/.e.txt"]);
+
+        options.dotglob = true;
+        assert_eq!(glob(&root, "*.txt", &options), [".hidden.txt", "a.txt", "b.txt"]);
+        assert_eq!(glob(&root, "*/", &options), [".config/", "sub/"]);
+        assert_eq!(glob(&root, "sub/*", &options), ["sub/.e.txt", "sub/d.txt"]);
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
@@ -0,0 +1,15 @@
+mod arithmetic;
+mod brace;
+mod expanded_command;
+mod expander;
+mod fatal;
+mod field;
+mod glob;
+mod parameter;
+mod pattern;
+mod tilde;
+
+pub use expanded_command::ExpandedCommand;
+pub use expander::Expander;
+pub use fatal::FatalExpansion;
+pub use field::DEFAULT_IFS;
@@ -0,0 +1,179 @@
+use crate::parser::is_name;
+use anyhow::Result;
+
+/// Comportement de `${NOM-mot}` et de ses variantes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Condition {
+    /// `-` : `mot` si le paramètre n'est pas défini
+    UseDefault,
+    /// `=` : idem, et le paramètre reçoit `mot`
+    AssignDefault,
+    /// `?` : erreur avec le message `mot`
+    ErrorIfUnset,
+    /// `+` : `mot` si le paramètre est défini, vide sinon
+    UseAlternative,
+}
+
+/// Forme de `${NOM/motif/remplacement}`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReplaceMode {
+    /// `/` : première occurrence
+    First,
+    /// `//` : toutes les occurrences
+    All,
+    /// `/#` : uniquement en début de valeur
+    Prefix,
+    /// `/%` : uniquement en fin de valeur
+    Suffix,
+}
+
+/// Contenu de `${...}` décomposé ; les opérandes sont gardées brutes et
+/// expansées seulement si elles sont utilisées.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParameterExpansion {
+    /// `${NOM}`
+    Value(String),
+    /// `${#NOM}`
+    Length(String),
+    /// `${NOM:-mot}`, `${NOM-mot}`... ; `null_is_unset` pour la forme avec `:`
+    Conditional {
+        name: String,
+        condition: Condition,
+        null_is_unset: bool,
+        word: String,
+    },
+    /// `${NOM#motif}` et `${NOM##motif}`
+    RemovePrefix {
+        name: String,
+        pattern: String,
+        longest: bool,
+    },
+    /// `${NOM%motif}` et `${NOM%%motif}`
+    RemoveSuffix {
+        name: String,
+        pattern: String,
+        longest: bool,
+    },
+    /// `${NOM/motif/remplacement}` et ses variantes
+    Replace {
+        name: String,
+        pattern: String,
+        replacement: String,
+        mode: ReplaceMode,
+    },
+}
+
+impl ParameterExpansion {
+    /// Décompose le texte situé entre `${` et `}`
+    pub fn parse(content: &str) -> Result<Self> {
+        let bad_substitution = || anyhow::anyhow!("${{{}}}: bad substitution", content);
+
+        // `${#}` est le paramètre `#`, `${#NOM}` la longueur de `NOM`
+        if let Some(name) = content.strip_prefix('#') {
+            if !name.is_empty() && Self::parameter_name_len(name) == Some(name.len()) {
+                return Ok(Self::Length(name.to_string()));
+            }
+        }
+
+        let len = Self::parameter_name_len(content).ok_or_else(bad_substitution)?;
+        let (name, rest) = content.split_at(len);
+        let name = name.to_string();
+
+        let conditional = |condition, null_is_unset, word: &str| Self::Conditional {
+            name: name.clone(),
+            condition,
+            null_is_unset,
+            word: word.to_string(),
+        };
+
+        let (null_is_unset, operator) = match rest.strip_prefix(':') {
+            Some(operator) => (true, operator),
+            None => (false, rest),
+        };
+
+        let mut chars = operator.chars();
+        let expansion = match chars.next() {
+            None if !null_is_unset => Self::Value(name.clone()),
+            Some('-') => conditional(Condition::UseDefault, null_is_unset, chars.as_str()),
+            Some('=') => conditional(Condition::AssignDefault, null_is_unset, chars.as_str()),
+            Some('?') => conditional(Condition::ErrorIfUnset, null_is_unset, chars.as_str()),
+            Some('+') => conditional(Condition::UseAlternative, null_is_unset, chars.as_str()),
+            _ if null_is_unset => return Err(bad_substitution()),
+            Some('#') => {
+                let longest = operator.starts_with("##");
+                Self::RemovePrefix {
+                    name: name.clone(),
+                    pattern: operator[if longest { 2 } else { 1 }..].to_string(),
+                    longest,
+                }
+            }
+            Some('%') => {
+                let longest = operator.starts_with("%%");
+                Self::RemoveSuffix {
+                    name: name.clone(),
+                    pattern: operator[if longest { 2 } else { 1 }..].to_string(),
+                    longest,
+                }
+            }
+            Some('/') => {
+                let rest = chars.as_str();
+                let (mode, rest) = match rest.chars().next() {
+                    Some('/') => (ReplaceMode::All, &rest[1..]),
+                    Some('#') => (ReplaceMode::Prefix, &rest[1..]),
+                    Some('%') => (ReplaceMode::Suffix, &rest[1..]),
+                    _ => (ReplaceMode::First, rest),
+                };
+                let (pattern, replacement) = Self::split_replacement(rest);
+                Self::Replace {
+                    name: name.clone(),
+                    pattern: pattern.to_string(),
+                    replacement: replacement.to_string(),
+                    mode,
+                }
+            }
+            _ => return Err(bad_substitution()),
+        };
+
+        Ok(expansion)
+    }
+
+    /// Longueur du nom de paramètre en tête de `text` : nom de variable,
+    /// numéro de paramètre positionnel ou paramètre spécial.
+    fn parameter_name_len(text: &str) -> Option<usize> {
+        let first = text.chars().next()?;
+
+        if first.is_ascii_digit() {
+            return Some(text.chars().take_while(char::is_ascii_digit).count());
+        }
+        if matches!(first, '?' | '$' | '!' | '#' | '@' | '*' | '-') {
+            return Some(1);
+        }
+
+        let len = text
+            .chars()
+            .take_while(|c| c.is_ascii_alphanumeric() || *c == '_')
+            .count();
+        is_name(&text[..len]).then_some(len)
+    }
+
+    /// Sépare `motif/remplacement` au premier `/` qui n'est ni échappé ni
+    /// entre quotes
+    fn split_replacement(text: &str) -> (&str, &str) {
+        let mut quote = None;
+        let mut escaped = false;
+
+        for (i, c) in text.char_indices() {
+            match (c, quote) {
+                _ if escaped => escaped = false,
+                ('\\', Some('\'')) => {}
+                ('\\', _) => escaped = true,
+                (c @ ('\'' | '"'), None) => quote = Some(c),
+                (c, Some(q)) if c == q => quote = None,
+                ('/', None) => return (&text[..i], &text[i + 1..]),
+                _ => {}
+            }
+        }
+
+        (text, "")
+    }
+}
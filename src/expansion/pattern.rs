@@ -0,0 +1,366 @@
+/// Élément d'un motif compilé
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Token {
+    Literal(char),
+    /// `?` : un caractère quelconque
+    AnyChar,
+    /// `*` : une suite quelconque, éventuellement vide
+    AnyString,
+    /// `[...]` ou `[!...]`
+    Class { negated: bool, items: Vec<ClassItem> },
+    /// Motif étendu : `?(...)`, `*(...)`, `+(...)`, `@(...)` ou `!(...)`
+    Group { kind: GroupKind, alternatives: Vec<Vec<Token>> },
+}
+
+/// Nombre d'occurrences acceptées par un motif étendu
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum GroupKind {
+    /// `?(a|b)` : zéro ou une
+    ZeroOrOne,
+    /// `*(a|b)` : zéro ou plus
+    ZeroOrMore,
+    /// `+(a|b)` : une ou plus
+    OneOrMore,
+    /// `@(a|b)` : exactement une
+    ExactlyOne,
+    /// `!(a|b)` : tout sauf l'une des alternatives
+    Not,
+}
+
+impl GroupKind {
+    fn from_char(c: char) -> Option<Self> {
+        match c {
+            '?' => Some(Self::ZeroOrOne),
+            '*' => Some(Self::ZeroOrMore),
+            '+' => Some(Self::OneOrMore),
+            '@' => Some(Self::ExactlyOne),
+            '!' => Some(Self::Not),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum ClassItem {
+    Char(char),
+    Range(char, char),
+    /// `[:alpha:]`, `[:digit:]`...
+    Named(String),
+}
+
+impl ClassItem {
+    fn matches(&self, c: char) -> bool {
+        match self {
+            Self::Char(expected) => c == *expected,
+            Self::Range(start, end) => (*start..=*end).contains(&c),
+            Self::Named(name) => match name.as_str() {
+                "alnum" => c.is_alphanumeric(),
+                "alpha" => c.is_alphabetic(),
+                "blank" => c == ' ' || c == '\t',
+                "cntrl" => c.is_control(),
+                "digit" => c.is_ascii_digit(),
+                "graph" => c.is_ascii_graphic(),
+                "lower" => c.is_lowercase(),
+                "print" => c.is_ascii_graphic() || c == ' ',
+                "punct" => c.is_ascii_punctuation(),
+                "space" => c.is_whitespace(),
+                "upper" => c.is_uppercase(),
+                "xdigit" => c.is_ascii_hexdigit(),
+                _ => false,
+            },
+        }
+    }
+}
+
+impl Token {
+    fn matches(&self, c: char) -> bool {
+        match self {
+            Self::Literal(expected) => c == *expected,
+            Self::AnyChar => true,
+            Self::AnyString | Self::Group { .. } => false,
+            Self::Class { negated, items } => items.iter().any(|item| item.matches(c)) != *negated,
+        }
+    }
+}
+
+/// Motif du shell (`*`, `?`, `[...]` et motifs étendus `@(a|b)`...), tel
+/// qu'utilisé par `${VAR#motif}` et le globbing.
+///
+/// Un caractère précédé de `\` est littéral : c'est ainsi que les parties
+/// entre quotes d'un mot sont protégées (voir `escape`).
+#[derive(Debug, Clone)]
+pub struct Pattern {
+    tokens: Vec<Token>,
+}
+
+impl Pattern {
+    pub fn new(pattern: &str) -> Self {
+        let chars: Vec<char> = pattern.chars().collect();
+        Self {
+            tokens: Self::compile(&chars),
+        }
+    }
+
+    fn compile(chars: &[char]) -> Vec<Token> {
+        let mut tokens = Vec::new();
+        let mut i = 0;
+
+        while i < chars.len() {
+            if let Some((group, next)) = Self::parse_group(chars, i) {
+                tokens.push(group);
+                i = next;
+                continue;
+            }
+
+            match chars[i] {
+                '\\' if i + 1 < chars.len() => {
+                    tokens.push(Token::Literal(chars[i + 1]));
+                    i += 2;
+                    continue;
+                }
+                '*' => {
+                    // `**` équivaut à `*`
+                    if tokens.last() != Some(&Token::AnyString) {
+                        tokens.push(Token::AnyString);
+                    }
+                }
+                '?' => tokens.push(Token::AnyChar),
+                '[' => {
+                    if let Some((class, next)) = Self::parse_class(chars, i + 1) {
+                        tokens.push(class);
+                        i = next;
+                        continue;
+                    }
+                    // `[` sans `]` fermant : caractère ordinaire
+                    tokens.push(Token::Literal('['));
+                }
+                c => tokens.push(Token::Literal(c)),
+            }
+            i += 1;
+        }
+
+        tokens
+    }
+
+    /// Lit un motif étendu commençant à `start` (`@(a|b)`...) ; retourne le
+    /// groupe et l'indice suivant la `)` fermante.
+    fn parse_group(chars: &[char], start: usize) -> Option<(Token, usize)> {
+        let kind = GroupKind::from_char(*chars.get(start)?)?;
+        if chars.get(start + 1) != Some(&'(') {
+            return None;
+        }
+
+        let mut alternatives = Vec::new();
+        let mut alternative_start = start + 2;
+        let mut depth = 0;
+        let mut i = start + 2;
+
+        while i < chars.len() {
+            match chars[i] {
+                '\\' => i += 1,
+                '(' => depth += 1,
+                ')' if depth > 0 => depth -= 1,
+                '|' if depth == 0 => {
+                    alternatives.push(Self::compile(&chars[alternative_start..i]));
+                    alternative_start = i + 1;
+                }
+                ')' => {
+                    alternatives.push(Self::compile(&chars[alternative_start..i]));
+                    return Some((Token::Group { kind, alternatives }, i + 1));
+                }
+                _ => {}
+            }
+            i += 1;
+        }
+
+        // Parenthèse non fermée : caractères ordinaires
+        None
+    }
+
+    /// Lit une classe à partir de `start` (après le `[`) ; retourne la classe
+    /// et l'indice suivant le `]` fermant.
+    fn parse_class(chars: &[char], start: usize) -> Option<(Token, usize)> {
+        let mut i = start;
+        let negated = matches!(chars.get(i), Some('!' | '^'));
+        if negated {
+            i += 1;
+        }
+
+        let mut items = Vec::new();
+        let first = i;
+
+        loop {
+            let c = *chars.get(i)?;
+            // Un `]` en première position fait partie de la classe
+            if c == ']' && i > first {
+                return Some((Token::Class { negated, items }, i + 1));
+            }
+
+            if c == '[' && chars.get(i + 1) == Some(&':') {
+                let rest: String = chars[i + 2..].iter().collect();
+                if let Some(end) = rest.find(":]") {
+                    let name = rest[..end].to_string();
+                    i += 2 + name.chars().count() + 2;
+                    items.push(ClassItem::Named(name));
+                    continue;
+                }
+            }
+
+            let c = if c == '\\' {
+                i += 1;
+                *chars.get(i)?
+            } else {
+                c
+            };
+
+            if chars.get(i + 1) == Some(&'-') && chars.get(i + 2).is_some_and(|&end| end != ']') {
+                items.push(ClassItem::Range(c, chars[i + 2]));
+                i += 3;
+            } else {
+                items.push(ClassItem::Char(c));
+                i += 1;
+            }
+        }
+    }
+
+    /// Texte du motif s'il ne contient aucun caractère spécial
+    pub fn as_literal(&self) -> Option<String> {
+        self.tokens
+            .iter()
+            .map(|token| match token {
+                Token::Literal(c) => Some(*c),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Indique si le motif correspond à l'intégralité de `text`
+    pub fn matches(&self, text: &str) -> bool {
+        let text: Vec<char> = text.chars().collect();
+        Self::matches_tokens(&self.tokens, &text)
+    }
+
+    fn matches_tokens(tokens: &[Token], text: &[char]) -> bool {
+        let Some((token, rest)) = tokens.split_first() else {
+            return text.is_empty();
+        };
+
+        match token {
+            Token::AnyString => (0..=text.len()).any(|i| Self::matches_tokens(rest, &text[i..])),
+            Token::Group { kind, alternatives } => (0..=text.len()).any(|i| {
+                Self::matches_group(*kind, alternatives, &text[..i])
+                    && Self::matches_tokens(rest, &text[i..])
+            }),
+            token => text
+                .split_first()
+                .is_some_and(|(c, remaining)| token.matches(*c) && Self::matches_tokens(rest, remaining)),
+        }
+    }
+
+    /// Indique si `text` entier est accepté par un motif étendu
+    fn matches_group(kind: GroupKind, alternatives: &[Vec<Token>], text: &[char]) -> bool {
+        let any = |text: &[char]| alternatives.iter().any(|alt| Self::matches_tokens(alt, text));
+
+        match kind {
+            GroupKind::ExactlyOne => any(text),
+            GroupKind::ZeroOrOne => text.is_empty() || any(text),
+            GroupKind::Not => !any(text),
+            GroupKind::ZeroOrMore => Self::matches_repeated(alternatives, text),
+            GroupKind::OneOrMore => {
+                any(text) || (!text.is_empty() && Self::matches_repeated(alternatives, text))
+            }
+        }
+    }
+
+    /// `text` est une suite (éventuellement vide) d'occurrences non vides
+    /// des alternatives
+    fn matches_repeated(alternatives: &[Vec<Token>], text: &[char]) -> bool {
+        text.is_empty()
+            || (1..=text.len()).any(|i| {
+                alternatives.iter().any(|alt| Self::matches_tokens(alt, &text[..i]))
+                    && Self::matches_repeated(alternatives, &text[i..])
+            })
+    }
+}
+
+/// Protège les caractères spéciaux de `text` pour qu'il soit lu littéralement
+/// dans un motif.
+pub fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        if matches!(c, '*' | '?' | '[' | ']' | '(' | ')' | '|' | '\\') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn matches(pattern: &str, text: &str) -> bool {
+        Pattern::new(pattern).matches(text)
+    }
+
+    #[test]
+    fn wildcards() {
+        assert!(matches("*", ""));
+        assert!(matches("a*c", "abbc"));
+        assert!(matches("a*c", "ac"));
+        assert!(!matches("a*c", "abd"));
+        assert!(matches("a?c", "abc"));
+        assert!(!matches("a?c", "ac"));
+        assert!(matches("*.rs", "main.rs"));
+    }
+
+    #[test]
+    fn classes() {
+        assert!(matches("[abc]", "b"));
+        assert!(matches("[!abc]", "d"));
+        assert!(!matches("[!abc]", "a"));
+        assert!(matches("[a-c]x", "bx"));
+        assert!(matches("[[:digit:]][[:alpha:]]", "1a"));
+        assert!(!matches("[[:upper:]]", "a"));
+        assert!(matches("[[:blank:]]", "\t"));
+    }
+
+    #[test]
+    fn range_edges() {
+        // Les bornes sont incluses ; une plage inversée ne contient rien
+        assert!(matches("[a-c]", "a"));
+        assert!(matches("[a-c]", "c"));
+        assert!(!matches("[a-c]", "d"));
+        assert!(!matches("[c-a]", "b"));
+        assert!(matches("[0-9a-f]", "e"));
+    }
+
+    #[test]
+    fn escaped_characters_are_literal() {
+        assert!(matches("\\*", "*"));
+        assert!(!matches("\\*", "a"));
+        assert!(matches(&escape("a*[b]?"), "a*[b]?"));
+        assert!(!matches(&escape("a*"), "ab"));
+        assert_eq!(Pattern::new(&escape("a*b")).as_literal().as_deref(), Some("a*b"));
+        assert_eq!(Pattern::new("a*b").as_literal(), None);
+    }
+
+    #[test]
+    fn extglob() {
+        assert!(matches("@(foo|bar)", "bar"));
+        assert!(!matches("@(foo|bar)", "foobar"));
+        assert!(matches("?(a)b", "b"));
+        assert!(matches("?(a)b", "ab"));
+        assert!(!matches("?(a)b", "aab"));
+        assert!(matches("*(ab|c)", ""));
+        assert!(matches("*(ab|c)", "abcab"));
+        assert!(!matches("*(ab|c)", "abd"));
+        assert!(matches("+(ab)", "abab"));
+        assert!(!matches("+(ab)", ""));
+        assert!(matches("!(foo)", "bar"));
+        assert!(!matches("!(foo)", "foo"));
+        assert!(matches("!(*.c).h", "main.h"));
+        assert!(matches("file.@(c|h)", "file.h"));
+    }
+}
@@ -0,0 +1,144 @@
+use crate::parser::{Word, WordPart};
+use crate::utils::users;
+use crate::variables::Variables;
+
+/// Remplace le `~` en tête d'un mot (`~`, `~/src`, `~user`, `~+`, `~-`).
+///
+/// Le préfixe s'arrête au premier `/` et doit être entièrement hors quotes ;
+/// le dossier obtenu n'est ensuite ni découpé ni traité comme un motif.
+pub fn expand(word: &Word, variables: &Variables) -> Word {
+    expand_prefixes(word, variables, false)
+}
+
+/// Comme `expand`, mais pour la valeur d'une affectation : un `~` est aussi
+/// remplacé après chaque `:` (`PATH=~/bin:~/.local/bin`).
+pub fn expand_assignment(word: &Word, variables: &Variables) -> Word {
+    expand_prefixes(word, variables, true)
+}
+
+fn expand_prefixes(word: &Word, variables: &Variables, after_colon: bool) -> Word {
+    let mut result = Word::default();
+    // Indique si la position courante peut commencer un préfixe `~`
+    let mut at_start = true;
+
+    for (index, part) in word.parts.iter().enumerate() {
+        let WordPart::Unquoted(text) = part else {
+            result.push(part.clone());
+            at_start = false;
+            continue;
+        };
+
+        let is_last_part = index + 1 == word.parts.len();
+        let mut unquoted = String::new();
+        let mut rest = text.as_str();
+
+        loop {
+            if at_start && rest.starts_with('~') {
+                let end = rest
+                    .find(|c| c == '/' || (after_colon && c == ':'))
+                    .unwrap_or(rest.len());
+                // `~"nom"` : un préfixe qui se poursuit entre quotes reste tel quel
+                let complete = end < rest.len() || is_last_part;
+
+                if let Some(dir) = resolve(&rest[1..end], variables).filter(|_| complete) {
+                    if !unquoted.is_empty() {
+                        result.push(WordPart::Unquoted(std::mem::take(&mut unquoted)));
+                    }
+                    result.push(WordPart::SingleQuoted(dir));
+                    rest = &rest[end..];
+                }
+            }
+
+            match rest.find(':').filter(|_| after_colon) {
+                Some(colon) => {
+                    unquoted.push_str(&rest[..=colon]);
+                    rest = &rest[colon + 1..];
+                    at_start = true;
+                }
+                None => {
+                    at_start = after_colon && rest.is_empty() && unquoted.ends_with(':');
+                    unquoted.push_str(rest);
+                    break;
+                }
+            }
+        }
+
+        if !unquoted.is_empty() {
+            result.push(WordPart::Unquoted(unquoted));
+        }
+    }
+
+    result
+}
+
+/// Dossier désigné par ce qui suit le `~` ; `None` s'il est inconnu, le
+/// préfixe restant alors littéral
+fn resolve(prefix: &str, variables: &Variables) -> Option<String> {
+    match prefix {
+        "" => variables.get("HOME").or_else(users::current_home_dir),
+        "+" => variables.get("PWD").or_else(|| {
+            std::env::current_dir()
+                .ok()
+                .map(|dir| dir.to_string_lossy().into_owned())
+        }),
+        "-" => variables.get("OLDPWD"),
+        user => users::home_dir(user),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::Lexer;
+
+    fn tilde(text: &str) -> String {
+        expand(&Lexer::lex_word(text).unwrap(), &Variables::new()).to_string()
+    }
+
+    fn assignment(text: &str) -> String {
+        expand_assignment(&Lexer::lex_word(text).unwrap(), &Variables::new()).to_string()
+    }
+
+    /// Dossier attendu pour `prefix`, entre quotes comme dans le mot produit
+    fn quoted_dir(prefix: &str) -> String {
+        format!("'{}'", resolve(prefix, &Variables::new()).unwrap())
+    }
+
+    #[test]
+    fn home_directory() {
+        let home = quoted_dir("");
+        assert_eq!(tilde("~"), home);
+        assert_eq!(tilde("~/src"), format!("{}/src", home));
+        assert_eq!(tilde("~/a:~/b"), format!("{}/a:~/b", home));
+    }
+
+    #[test]
+    fn other_prefixes() {
+        assert_eq!(tilde("~+/x"), format!("{}/x", quoted_dir("+")));
+        match resolve("-", &Variables::new()) {
+            Some(dir) => assert_eq!(tilde("~-"), format!("'{}'", dir)),
+            None => assert_eq!(tilde("~-"), "~-"),
+        }
+        if let Some(dir) = users::home_dir("root") {
+            assert_eq!(tilde("~root/x"), format!("'{}'/x", dir));
+        }
+        assert_eq!(tilde("~no_such_user_for_tests/x"), "~no_such_user_for_tests/x");
+    }
+
+    #[test]
+    fn only_unquoted_leading_tilde() {
+        assert_eq!(tilde("a~"), "a~");
+        assert_eq!(tilde("'~'"), "'~'");
+        assert_eq!(tilde("\\~"), "'~'");
+        assert_eq!(tilde("~\"x\""), "~\"x\"");
+        assert_eq!(tilde("\"~\""), "\"~\"");
+    }
+
+    #[test]
+    fn assignments() {
+        let home = quoted_dir("");
+        assert_eq!(assignment("~/a:~/b"), format!("{}/a:{}/b", home, home));
+        assert_eq!(assignment("/x:~"), format!("/x:{}", home));
+        assert_eq!(assignment("a~:b"), "a~:b");
+    }
+}
@@ -1,13 +1,48 @@
+mod arguments;
 mod command;
 mod commands;
+mod expansion;
+mod options;
 mod shell;
+mod startup;
 mod utils;
+mod variables;
 mod parser;
 mod execution;
 
+use arguments::{Arguments, Source};
+use execution::terminal;
 use shell::Shell;
 
 fn main() {
+    let arguments = match Arguments::parse(std::env::args()) {
+        Ok(arguments) => arguments,
+        Err(err) => {
+            eprintln!("shell: {}", err);
+            eprintln!("{}", arguments::USAGE);
+            std::process::exit(2);
+        }
+    };
+
     let mut shell = Shell::new();
-    shell.run();
+    shell.set_script_name(arguments.name);
+    shell.set_positional_params(arguments.positional);
+    shell.set_errexit(arguments.errexit);
+    shell.set_xtrace(arguments.xtrace);
+
+    // Interactif par défaut seulement en lisant un terminal
+    let reads_terminal = arguments.source == Source::Stdin && terminal::is_terminal();
+    shell.set_interactive(arguments.interactive || reads_terminal);
+
+    startup::load(&mut shell, arguments.login, !arguments.norc);
+
+    let status = match arguments.source {
+        Source::Stdin => shell.run(),
+        Source::Command(command) => shell.execute_command(&command),
+        Source::Script(path) => shell.run_script(&path),
+    };
+
+    // Rétablir le terminal avant de quitter
+    drop(shell);
+    std::process::exit(status);
 }
@@ -0,0 +1,48 @@
+use anyhow::{bail, Result};
+
+/// Options du shell modifiables avec `shopt`
+#[derive(Debug, Clone, Default)]
+pub struct ShellOptions {
+    /// Les motifs correspondent aussi aux fichiers cachés
+    pub dotglob: bool,
+    /// Un motif sans correspondance est une erreur : la commande n'est pas lancée
+    pub failglob: bool,
+    /// `**` descend dans les liens symboliques vers des dossiers (sans
+    /// jamais repasser par un dossier déjà visité)
+    pub followlinks: bool,
+    /// Un motif sans correspondance disparaît de la ligne de commande
+    pub nullglob: bool,
+}
+
+impl ShellOptions {
+    /// Noms des options, dans l'ordre d'affichage
+    pub const NAMES: [&'static str; 4] = ["dotglob", "failglob", "followlinks", "nullglob"];
+
+    fn flag_mut(&mut self, name: &str) -> Option<&mut bool> {
+        match name {
+            "dotglob" => Some(&mut self.dotglob),
+            "failglob" => Some(&mut self.failglob),
+            "followlinks" => Some(&mut self.followlinks),
+            "nullglob" => Some(&mut self.nullglob),
+            _ => None,
+        }
+    }
+
+    pub fn get(&self, name: &str) -> Option<bool> {
+        match name {
+            "dotglob" => Some(self.dotglob),
+            "failglob" => Some(self.failglob),
+            "followlinks" => Some(self.followlinks),
+            "nullglob" => Some(self.nullglob),
+            _ => None,
+        }
+    }
+
+    pub fn set(&mut self, name: &str, value: bool) -> Result<()> {
+        match self.flag_mut(name) {
+            Some(flag) => *flag = value,
+            None => bail!("{}: invalid shell option name", name),
+        }
+        Ok(())
+    }
+}
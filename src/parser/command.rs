@@ -0,0 +1,52 @@
+use std::fmt;
+
+use crate::parser::command_list::CommandList;
+use crate::parser::parsed_command::ParsedCommand;
+use crate::parser::redirection::Redirection;
+use crate::parser::word::Word;
+
+/// Étape d'un pipeline : commande simple ou commande composée
+#[derive(Debug)]
+pub enum Command {
+    Simple(ParsedCommand),
+    If(IfClause),
+}
+
+impl fmt::Display for Command {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Simple(command) => write!(f, "{}", command),
+            Self::If(clause) => write!(f, "{}", clause),
+        }
+    }
+}
+
+/// `if condition; then corps; [elif condition; then corps;]... [else corps;] fi`
+///
+/// Les conditions sont évaluées dans l'ordre : le corps de la première qui
+/// réussit (code de retour 0) est exécuté, sinon celui du `else`.
+#[derive(Debug)]
+pub struct IfClause {
+    /// Couples condition / corps du `if` puis de chaque `elif`
+    pub branches: Vec<(CommandList, CommandList)>,
+    pub else_body: Option<CommandList>,
+    /// Redirections placées après `fi`, valables pour toute la commande
+    pub redirections: Vec<Redirection<Word>>,
+}
+
+impl fmt::Display for IfClause {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (index, (condition, body)) in self.branches.iter().enumerate() {
+            let keyword = if index == 0 { "if" } else { "elif" };
+            write!(f, "{} {} then {} ", keyword, condition, body)?;
+        }
+        if let Some(body) = &self.else_body {
+            write!(f, "else {} ", body)?;
+        }
+        write!(f, "fi")?;
+        for redirection in &self.redirections {
+            write!(f, " {}", redirection)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,67 @@
+use std::fmt;
+
+use crate::parser::pipeline::Pipeline;
+
+/// Opérateur reliant deux pipelines d'une liste `&&` / `||`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LogicalOperator {
+    And, // &&
+    Or,  // ||
+}
+
+impl LogicalOperator {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::And => "&&",
+            Self::Or => "||",
+        }
+    }
+}
+
+/// Pipelines reliés par `&&` et `||`, évalués de gauche à droite : chaque
+/// pipeline n'est exécuté que si le code de retour précédent le permet.
+#[derive(Debug)]
+pub struct AndOrList {
+    pub first: Pipeline,
+    pub rest: Vec<(LogicalOperator, Pipeline)>,
+    /// Terminée par `&` : exécutée en arrière-plan, sans attendre sa fin
+    pub background: bool,
+}
+
+/// Réécrit la liste telle qu'elle a été saisie, sans le `&` final
+impl fmt::Display for AndOrList {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.first)?;
+        for (operator, pipeline) in &self.rest {
+            write!(f, " {} {}", operator.as_str(), pipeline)?;
+        }
+        Ok(())
+    }
+}
+
+/// Ligne de commande complète : des listes `&&` / `||` séparées par `;` ou `&`
+#[derive(Debug)]
+pub struct CommandList {
+    pub items: Vec<AndOrList>,
+}
+
+impl CommandList {
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+}
+
+/// Réécrit la liste sur une ligne, chaque élément suivi de `;` ou `&`
+impl fmt::Display for CommandList {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let items: Vec<String> = self
+            .items
+            .iter()
+            .map(|item| match item.background {
+                true => format!("{} &", item),
+                false => format!("{};", item),
+            })
+            .collect();
+        write!(f, "{}", items.join(" "))
+    }
+}
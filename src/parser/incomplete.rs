@@ -0,0 +1,24 @@
+use std::fmt;
+
+/// L'entrée s'arrête au milieu d'une construction (here-document non
+/// terminé...) : le shell doit lire des lignes supplémentaires.
+#[derive(Debug)]
+pub struct IncompleteInput {
+    reason: String,
+}
+
+impl IncompleteInput {
+    pub fn new(reason: impl Into<String>) -> Self {
+        Self {
+            reason: reason.into(),
+        }
+    }
+}
+
+impl fmt::Display for IncompleteInput {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "unexpected end of input: {}", self.reason)
+    }
+}
+
+impl std::error::Error for IncompleteInput {}
@@ -1,8 +1,24 @@
+use crate::parser::incomplete::IncompleteInput;
+use crate::parser::scan::{read_backquoted, read_until_closing};
 use crate::parser::token::{FileDescriptor, RedirectMode, Token};
+use crate::parser::word::{Word, WordPart};
 use anyhow::{bail, Result};
+use std::iter::Peekable;
+use std::str::Chars;
 
 pub struct Lexer;
 
+/// Here-document dont le corps reste à lire après la fin de la ligne
+struct PendingHereDoc {
+    /// Position du `Token::HereDoc` à compléter
+    index: usize,
+    delimiter: String,
+    /// Délimiteur entre quotes : le corps n'est pas expansé
+    quoted: bool,
+    /// `<<-` : les tabulations en début de ligne sont supprimées
+    strip_tabs: bool,
+}
+
 #[derive(PartialEq, Debug)]
 enum LexerState {
     Default,
@@ -17,22 +33,32 @@ impl Lexer {
         let mut tokens = Vec::with_capacity(input.len() / 4);
         let mut state = LexerState::Default;
         let mut curr = String::with_capacity(32);
+        let mut pending_heredocs: Vec<PendingHereDoc> = Vec::new();
 
         let mut chars = input.chars().peekable();
 
         while let Some(ch) = chars.next() {
             state = match (state, ch) {
-                // État Escaped (hors quotes)
+                // État Escaped - Newline : continuation de ligne
+                (LexerState::Escaped, '\n') => LexerState::Default,
+
+                // État Escaped (hors quotes) : le caractère est pris littéralement
                 (LexerState::Escaped, ch) => {
-                    curr.push(ch);
+                    Self::push_word_if_not_empty(&mut tokens, &mut curr);
+                    tokens.push(Token::QuotedString(ch.to_string(), '\\'));
                     LexerState::Default
                 }
 
                 // État EscapedInDoubleQuote
                 (LexerState::EscapedInDoubleQuote, ch) => {
                     match ch {
-                        '"' | '\\' => {
-                            curr.push(ch);
+                        '"' | '\\' | '$' | '`' => {
+                            // Isoler le caractère pour qu'il échappe à l'expansion
+                            if !curr.is_empty() {
+                                tokens.push(Token::QuotedString(curr.clone(), '"'));
+                                curr.clear();
+                            }
+                            tokens.push(Token::QuotedString(ch.to_string(), '\\'));
                         }
                         _ => {
                             curr.push('\\');
@@ -43,46 +69,142 @@ impl Lexer {
                 }
 
                 // Default - Whitespace
-                (LexerState::Default, ' ') => {
+                (LexerState::Default, ' ' | '\t') => {
                     Self::push_word_if_not_empty(&mut tokens, &mut curr);
                     tokens.push(Token::Whitespace);
                     LexerState::Default
                 }
 
+                // Default - Commentaire : `#` en début de mot, jusqu'à la fin de la ligne
+                (LexerState::Default, '#')
+                    if curr.is_empty()
+                        && !matches!(tokens.last(), Some(Token::Litteral(_) | Token::QuotedString(..))) =>
+                {
+                    while chars.next_if(|&next| next != '\n').is_some() {}
+                    LexerState::Default
+                }
+
                 // Default - Backslash
                 (LexerState::Default, '\\') => LexerState::Escaped,
 
                 // Default - Single Quote
-                (LexerState::Default, '\'') => LexerState::SingleQuoted,
+                (LexerState::Default, '\'') => {
+                    Self::push_word_if_not_empty(&mut tokens, &mut curr);
+                    LexerState::SingleQuoted
+                }
 
                 // Default - Double Quote
-                (LexerState::Default, '"') => LexerState::DoubleQuoted,
+                (LexerState::Default, '"') => {
+                    Self::push_word_if_not_empty(&mut tokens, &mut curr);
+                    LexerState::DoubleQuoted
+                }
 
-                // Default - Newline
-                (LexerState::Default, '\n') => LexerState::Default,
+                // Default - Newline : fin de commande, puis corps des here-documents
+                (LexerState::Default, '\n') => {
+                    Self::push_word_if_not_empty(&mut tokens, &mut curr);
+                    tokens.push(Token::Newline);
+
+                    for heredoc in pending_heredocs.drain(..) {
+                        let body = Self::read_heredoc_body(&mut chars, &heredoc)?;
+                        if let Token::HereDoc { body: token_body, .. } = &mut tokens[heredoc.index] {
+                            *token_body = body;
+                        }
+                    }
+                    LexerState::Default
+                }
+
+                // Default - Substitution de processus `<(...)` / `>(...)`
+                (LexerState::Default, '<' | '>') if chars.peek() == Some(&'(') => {
+                    Self::read_parenthesized(ch, &mut chars, &mut curr, "unclosed process substitution")?;
+                    LexerState::Default
+                }
 
                 // Default - Redirect Operator
                 (LexerState::Default, '>') => {
-                    let fd = if curr.is_empty() {
-                        FileDescriptor::Stdout
-                    } else {
-                        match FileDescriptor::from_str(&curr) {
-                            Ok(fd) => {
-                                curr.clear();
-                                fd
-                            }
-                            Err(_) => {
-                                // Si c'est UNIQUEMENT un nombre, c'est un FD invalide
-                                if curr.chars().all(|c| c.is_ascii_digit()) {
-                                    bail!("Bad file descriptor: {}", curr);
-                                }
-                                // Sinon, c'est un mot normal (ex: "hello3")
-                                Self::push_word_if_not_empty(&mut tokens, &mut curr);
-                                FileDescriptor::Stdout
+                    let fd = Self::take_redirect_fd(&mut tokens, &mut curr, FileDescriptor::STDOUT)?;
+
+                    let mode = match chars.peek() {
+                        Some('>') => {
+                            chars.next();
+                            RedirectMode::Append
+                        }
+                        Some('&') => {
+                            chars.next();
+                            RedirectMode::Duplicate
+                        }
+                        _ => RedirectMode::Overwrite,
+                    };
+
+                    tokens.push(Token::Redirect { mode, fd });
+                    LexerState::Default
+                }
+
+                // Default - Input Redirect Operator
+                (LexerState::Default, '<') => {
+                    let fd = Self::take_redirect_fd(&mut tokens, &mut curr, FileDescriptor::STDIN)?;
+
+                    let mode = match chars.peek() {
+                        Some('&') => {
+                            chars.next();
+                            RedirectMode::Duplicate
+                        }
+                        Some('<') => {
+                            chars.next();
+                            if chars.peek() == Some(&'<') {
+                                chars.next();
+                                RedirectMode::HereString
+                            } else {
+                                RedirectMode::HereDoc
                             }
                         }
+                        _ => RedirectMode::Input,
                     };
 
+                    if mode == RedirectMode::HereDoc {
+                        let strip_tabs = chars.peek() == Some(&'-');
+                        if strip_tabs {
+                            chars.next();
+                        }
+
+                        let (delimiter, quoted) = Self::read_heredoc_delimiter(&mut chars)?;
+                        pending_heredocs.push(PendingHereDoc {
+                            index: tokens.len(),
+                            delimiter,
+                            quoted,
+                            strip_tabs,
+                        });
+                        tokens.push(Token::HereDoc { fd, body: Word::default() });
+                    } else {
+                        tokens.push(Token::Redirect { mode, fd });
+                    }
+                    LexerState::Default
+                }
+
+                // Default - Pipe ou Or
+                (LexerState::Default, '|') => {
+                    Self::push_word_if_not_empty(&mut tokens, &mut curr);
+                    if chars.peek() == Some(&'|') {
+                        chars.next();
+                        tokens.push(Token::Or);
+                    } else {
+                        tokens.push(Token::Pipe);
+                    }
+                    LexerState::Default
+                }
+
+                // Default - And
+                (LexerState::Default, '&') if chars.peek() == Some(&'&') => {
+                    chars.next();
+                    Self::push_word_if_not_empty(&mut tokens, &mut curr);
+                    tokens.push(Token::And);
+                    LexerState::Default
+                }
+
+                // Default - Redirection de stdout et stderr (`&>`, `&>>`)
+                (LexerState::Default, '&') if chars.peek() == Some(&'>') => {
+                    chars.next();
+                    Self::push_word_if_not_empty(&mut tokens, &mut curr);
+
                     let mode = if chars.peek() == Some(&'>') {
                         chars.next();
                         RedirectMode::Append
@@ -90,7 +212,33 @@ impl Lexer {
                         RedirectMode::Overwrite
                     };
 
-                    tokens.push(Token::Redirect { mode, fd });
+                    tokens.push(Token::RedirectAll { mode });
+                    LexerState::Default
+                }
+
+                // Default - Exécution en arrière-plan
+                (LexerState::Default, '&') => {
+                    Self::push_word_if_not_empty(&mut tokens, &mut curr);
+                    tokens.push(Token::Background);
+                    LexerState::Default
+                }
+
+                // Default - Séparateur de commandes
+                (LexerState::Default, ';') => {
+                    Self::push_word_if_not_empty(&mut tokens, &mut curr);
+                    tokens.push(Token::Semicolon);
+                    LexerState::Default
+                }
+
+                // Default - `${...}`, `$(...)`, `` `...` `` : lus d'un bloc
+                (LexerState::Default, '$' | '`') => {
+                    Self::read_expansion(ch, &mut chars, &mut curr)?;
+                    LexerState::Default
+                }
+
+                // Default - Motif étendu (`@(a|b)`...) : `|` n'y est pas un pipe
+                (LexerState::Default, '?' | '*' | '+' | '@' | '!') if chars.peek() == Some(&'(') => {
+                    Self::read_parenthesized(ch, &mut chars, &mut curr, "unclosed extended pattern")?;
                     LexerState::Default
                 }
 
@@ -123,6 +271,12 @@ impl Lexer {
                 // Double Quoted - Backslash
                 (LexerState::DoubleQuoted, '\\') => LexerState::EscapedInDoubleQuote,
 
+                // Double Quoted - Expansions lues d'un bloc
+                (LexerState::DoubleQuoted, '$' | '`') => {
+                    Self::read_expansion(ch, &mut chars, &mut curr)?;
+                    LexerState::DoubleQuoted
+                }
+
                 // Double Quoted - Autre caractère
                 (LexerState::DoubleQuoted, ch) => {
                     curr.push(ch);
@@ -133,20 +287,273 @@ impl Lexer {
 
         // Vérifications finales
         match state {
-            LexerState::SingleQuoted => bail!("Unclosed single quote"),
-            LexerState::DoubleQuoted => bail!("Unclosed double quote"),
-            LexerState::EscapedInDoubleQuote => {
-                bail!("Trailing backslash in double quote")
+            LexerState::SingleQuoted => {
+                return Err(IncompleteInput::new("unclosed single quote").into())
             }
-            LexerState::Escaped => bail!("Trailing backslash"),
+            LexerState::DoubleQuoted | LexerState::EscapedInDoubleQuote => {
+                return Err(IncompleteInput::new("unclosed double quote").into())
+            }
+            LexerState::Escaped => return Err(IncompleteInput::new("trailing backslash").into()),
             LexerState::Default => {
                 Self::push_word_if_not_empty(&mut tokens, &mut curr);
             }
         }
 
+        if let Some(heredoc) = pending_heredocs.first() {
+            return Err(IncompleteInput::new(format!(
+                "here-document delimited by `{}'",
+                heredoc.delimiter
+            ))
+            .into());
+        }
+
         Ok(tokens)
     }
 
+    /// Lit le délimiteur qui suit `<<` ; toute forme de quote dans le
+    /// délimiteur désactive l'expansion du corps.
+    fn read_heredoc_delimiter(chars: &mut Peekable<Chars>) -> Result<(String, bool)> {
+        while matches!(chars.peek(), Some(' ') | Some('\t')) {
+            chars.next();
+        }
+
+        let mut delimiter = String::new();
+        let mut quoted = false;
+
+        while let Some(&ch) = chars.peek() {
+            match ch {
+                ' ' | '\t' | '\n' | ';' | '|' | '&' | '<' | '>' => break,
+                '\'' | '"' => {
+                    chars.next();
+                    quoted = true;
+                    loop {
+                        match chars.next() {
+                            Some(c) if c == ch => break,
+                            Some(c) => delimiter.push(c),
+                            None => bail!("Unclosed quote in here-document delimiter"),
+                        }
+                    }
+                }
+                '\\' => {
+                    chars.next();
+                    quoted = true;
+                    if let Some(c) = chars.next() {
+                        delimiter.push(c);
+                    }
+                }
+                _ => {
+                    chars.next();
+                    delimiter.push(ch);
+                }
+            }
+        }
+
+        if delimiter.is_empty() && !quoted {
+            bail!("syntax error near unexpected token `newline'");
+        }
+        Ok((delimiter, quoted))
+    }
+
+    /// Lit les lignes d'un here-document jusqu'à la ligne du délimiteur
+    fn read_heredoc_body(chars: &mut Peekable<Chars>, heredoc: &PendingHereDoc) -> Result<Word> {
+        let mut body = String::new();
+
+        loop {
+            if chars.peek().is_none() {
+                return Err(IncompleteInput::new(format!(
+                    "here-document delimited by `{}'",
+                    heredoc.delimiter
+                ))
+                .into());
+            }
+
+            let mut line: String = chars.by_ref().take_while(|&c| c != '\n').collect();
+            if heredoc.strip_tabs {
+                line = line.trim_start_matches('\t').to_string();
+            }
+
+            if line == heredoc.delimiter {
+                break;
+            }
+            body.push_str(&line);
+            body.push('\n');
+        }
+
+        if heredoc.quoted {
+            return Ok(Word::literal(&body));
+        }
+        Ok(Self::heredoc_word(&body))
+    }
+
+    /// Découpe un corps de here-document expansible : comme entre quotes
+    /// doubles, `\$`, `` \` `` et `\\` restent littéraux et `\` suivi
+    /// d'un retour à la ligne joint les deux lignes.
+    fn heredoc_word(body: &str) -> Word {
+        let mut word = Word::default();
+        let mut curr = String::new();
+        let mut chars = body.chars().peekable();
+
+        while let Some(ch) = chars.next() {
+            match (ch, chars.peek()) {
+                ('\\', Some(&next @ ('$' | '`' | '\\'))) => {
+                    chars.next();
+                    if !curr.is_empty() {
+                        word.push(WordPart::DoubleQuoted(std::mem::take(&mut curr)));
+                    }
+                    word.push(WordPart::SingleQuoted(next.to_string()));
+                }
+                ('\\', Some('\n')) => {
+                    chars.next();
+                }
+                (ch, _) => curr.push(ch),
+            }
+        }
+
+        // Un corps vide reste un mot (ne pas confondre avec une cible absente)
+        word.push(WordPart::DoubleQuoted(curr));
+        word
+    }
+
+    /// Recopie tel quel dans le mot courant un `${...}`, `$(...)` ou
+    /// `` `...` `` dont le premier caractère `ch` vient d'être lu : espaces,
+    /// quotes et opérateurs qu'il contient ne coupent pas le mot. Tout autre
+    /// `$` est un caractère ordinaire.
+    fn read_expansion(ch: char, chars: &mut Peekable<Chars>, curr: &mut String) -> Result<()> {
+        match (ch, chars.peek()) {
+            ('$', Some(&open @ ('{' | '('))) => {
+                chars.next();
+                let close = if open == '{' { '}' } else { ')' };
+                let content = read_until_closing(chars, open, close).ok_or_else(|| {
+                    IncompleteInput::new(if open == '{' { "unclosed `${'" } else { "unclosed `$('" })
+                })?;
+
+                curr.push('$');
+                curr.push(open);
+                curr.push_str(&content);
+                curr.push(close);
+            }
+            ('`', _) => {
+                let content =
+                    read_backquoted(chars).ok_or_else(|| IncompleteInput::new("unclosed backquote"))?;
+
+                curr.push('`');
+                curr.push_str(&content);
+                curr.push('`');
+            }
+            (ch, _) => curr.push(ch),
+        }
+        Ok(())
+    }
+
+    /// Recopie `ch(...)` dans le mot courant, `ch` déjà consommé et `(` à
+    /// venir ; `reason` décrit l'erreur si la parenthèse n'est pas fermée
+    fn read_parenthesized(
+        ch: char,
+        chars: &mut Peekable<Chars>,
+        curr: &mut String,
+        reason: &str,
+    ) -> Result<()> {
+        chars.next();
+        let content = read_until_closing(chars, '(', ')').ok_or_else(|| IncompleteInput::new(reason))?;
+
+        curr.push(ch);
+        curr.push('(');
+        curr.push_str(&content);
+        curr.push(')');
+        Ok(())
+    }
+
+    /// Découpe l'opérande d'une expansion (`${NOM:-mot}`) en fragments selon
+    /// ses quotes ; contrairement à `lex`, les blancs et opérateurs y sont
+    /// de simples caractères.
+    pub fn lex_word(input: &str) -> Result<Word> {
+        let mut word = Word::default();
+        let mut curr = String::new();
+        let mut chars = input.chars().peekable();
+
+        let flush = |word: &mut Word, curr: &mut String| {
+            if !curr.is_empty() {
+                word.push(WordPart::Unquoted(std::mem::take(curr)));
+            }
+        };
+
+        while let Some(ch) = chars.next() {
+            match ch {
+                '\\' => {
+                    flush(&mut word, &mut curr);
+                    if let Some(next) = chars.next() {
+                        word.push(WordPart::SingleQuoted(next.to_string()));
+                    }
+                }
+                '\'' => {
+                    flush(&mut word, &mut curr);
+                    let text: String = chars.by_ref().take_while(|&c| c != '\'').collect();
+                    word.push(WordPart::SingleQuoted(text));
+                }
+                '"' => {
+                    flush(&mut word, &mut curr);
+                    let mut text = String::new();
+                    while let Some(c) = chars.next() {
+                        match (c, chars.peek()) {
+                            ('"', _) => break,
+                            ('\\', Some(&next @ ('"' | '\\' | '$' | '`'))) => {
+                                chars.next();
+                                if !text.is_empty() {
+                                    word.push(WordPart::DoubleQuoted(std::mem::take(&mut text)));
+                                }
+                                word.push(WordPart::SingleQuoted(next.to_string()));
+                            }
+                            (c @ ('$' | '`'), _) => Self::read_expansion(c, &mut chars, &mut text)?,
+                            (c, _) => text.push(c),
+                        }
+                    }
+                    // `""` reste un fragment : la valeur est vide mais présente
+                    word.push(WordPart::DoubleQuoted(text));
+                }
+                '$' | '`' => Self::read_expansion(ch, &mut chars, &mut curr)?,
+                ch => curr.push(ch),
+            }
+        }
+
+        flush(&mut word, &mut curr);
+        Ok(word)
+    }
+
+    /// Interprète le mot collé devant un opérateur de redirection (ex: `2>`)
+    /// comme un descripteur ; à défaut, `default` est utilisé.
+    fn take_redirect_fd(
+        tokens: &mut Vec<Token>,
+        curr: &mut String,
+        default: FileDescriptor,
+    ) -> Result<FileDescriptor> {
+        if curr.is_empty() {
+            return Ok(default);
+        }
+
+        // Des chiffres collés à un fragment précédent (`"a"2>`) terminent ce
+        // mot au lieu d'en commencer un nouveau
+        if matches!(tokens.last(), Some(Token::Litteral(_) | Token::QuotedString(..))) {
+            Self::push_word_if_not_empty(tokens, curr);
+            return Ok(default);
+        }
+
+        match FileDescriptor::from_str(curr) {
+            Ok(fd) => {
+                curr.clear();
+                Ok(fd)
+            }
+            Err(_) => {
+                // Si c'est UNIQUEMENT un nombre, c'est un FD invalide
+                if curr.chars().all(|c| c.is_ascii_digit()) {
+                    bail!("Bad file descriptor: {}", curr);
+                }
+                // Sinon, c'est un mot normal (ex: "hello3")
+                Self::push_word_if_not_empty(tokens, curr);
+                Ok(default)
+            }
+        }
+    }
+
     #[inline]
     fn push_word_if_not_empty(tokens: &mut Vec<Token>, curr: &mut String) {
         if !curr.is_empty() {
@@ -155,3 +562,117 @@ impl Lexer {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Représentation compacte des tokens produits pour `input`
+    fn tokens(input: &str) -> Vec<String> {
+        Lexer::lex(input)
+            .unwrap()
+            .iter()
+            .map(|token| match token {
+                Token::Litteral(text) => text.clone(),
+                Token::QuotedString(text, '\\') => format!("\\{}", text),
+                Token::QuotedString(text, quote) => format!("{}{}{}", quote, text, quote),
+                Token::Whitespace => " ".to_string(),
+                Token::Redirect { mode, fd } => format!("{}{:?}", fd.index(), mode),
+                Token::RedirectAll { mode } => format!("&{:?}", mode),
+                Token::HereDoc { fd, body } => format!("{}<<{}", fd.index(), body),
+                Token::Pipe => "|".to_string(),
+                Token::And => "&&".to_string(),
+                Token::Or => "||".to_string(),
+                Token::Semicolon => ";".to_string(),
+                Token::Background => "&".to_string(),
+                Token::Newline => "\\n".to_string(),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn pipes() {
+        assert_eq!(tokens("ls | wc -l"), ["ls", " ", "|", " ", "wc", " ", "-l"]);
+        assert_eq!(tokens("a|b|c"), ["a", "|", "b", "|", "c"]);
+        assert_eq!(tokens("echo 'a|b' \"|\""), ["echo", " ", "'a|b'", " ", "\"|\""]);
+        assert_eq!(tokens("echo a\\|b"), ["echo", " ", "a", "\\|", "b"]);
+    }
+
+    #[test]
+    fn list_operators() {
+        assert_eq!(tokens("a;b"), ["a", ";", "b"]);
+        assert_eq!(tokens("a && b || c"), ["a", " ", "&&", " ", "b", " ", "||", " ", "c"]);
+        assert_eq!(tokens("a&&b||c&"), ["a", "&&", "b", "||", "c", "&"]);
+        assert_eq!(tokens("a\nb"), ["a", "\\n", "b"]);
+        assert_eq!(tokens("echo ';' \\;"), ["echo", " ", "';'", " ", "\\;"]);
+    }
+
+    #[test]
+    fn io_numbers() {
+        assert_eq!(tokens("cmd 2>&1"), ["cmd", " ", "2Duplicate", "1"]);
+        assert_eq!(tokens("cmd >&2"), ["cmd", " ", "1Duplicate", "2"]);
+        assert_eq!(tokens("cmd 3<&-"), ["cmd", " ", "3Duplicate", "-"]);
+        assert_eq!(tokens("cmd 2>>log"), ["cmd", " ", "2Append", "log"]);
+        assert_eq!(tokens("cmd &>out &>>log"), ["cmd", " ", "&Overwrite", "out", " ", "&Append", "log"]);
+
+        // Seul un chiffre isolé en début de mot est un descripteur
+        assert_eq!(tokens("echo a2>f"), ["echo", " ", "a2", "1Overwrite", "f"]);
+        assert_eq!(tokens("echo 2 >f"), ["echo", " ", "2", " ", "1Overwrite", "f"]);
+        assert_eq!(tokens("echo \"a\"2>/dev/null"), ["echo", " ", "\"a\"", "2", "1Overwrite", "/dev/null"]);
+        assert_eq!(tokens("echo '2'>f"), ["echo", " ", "'2'", "1Overwrite", "f"]);
+        assert!(Lexer::lex("echo 10>f").is_err());
+    }
+
+    #[test]
+    fn heredocs() {
+        assert_eq!(tokens("cat <<EOF\nhello $USER\nEOF\n"), ["cat", " ", "0<<\"hello $USER\n\"", "\\n"]);
+        assert_eq!(tokens("cat <<EOF\nEOF"), ["cat", " ", "0<<\"\"", "\\n"]);
+
+        // Délimiteur quoté : corps littéral
+        assert_eq!(tokens("cat <<'EOF'\n$x\nEOF"), ["cat", " ", "0<<'$x\n'", "\\n"]);
+        assert_eq!(tokens("cat <<\\EOF\n$x\nEOF"), ["cat", " ", "0<<'$x\n'", "\\n"]);
+
+        // `<<-` retire les tabulations de tête, délimiteur compris
+        assert_eq!(tokens("cat <<-EOF\n\t\ta\n\tEOF\n"), ["cat", " ", "0<<\"a\n\"", "\\n"]);
+
+        // `\$` reste littéral, `\` en fin de ligne joint les lignes
+        assert_eq!(tokens("cat <<EOF\n\\$x a\\\nb\nEOF"), ["cat", " ", "0<<'$'\"x ab\n\"", "\\n"]);
+
+        // Plusieurs documents sur une ligne sont lus dans l'ordre
+        assert_eq!(
+            tokens("cat <<A 3<<B\na\nA\nb\nB"),
+            ["cat", " ", "0<<\"a\n\"", " ", "3<<\"b\n\"", "\\n"]
+        );
+
+        assert_eq!(tokens("cat <<<word"), ["cat", " ", "0HereString", "word"]);
+        assert!(Lexer::lex("cat <<").is_err());
+    }
+
+    #[test]
+    fn incomplete_input() {
+        let is_incomplete = |input: &str| {
+            matches!(Lexer::lex(input), Err(err) if err.is::<IncompleteInput>())
+        };
+
+        assert!(is_incomplete("cat <<EOF"));
+        assert!(is_incomplete("cat <<EOF\nbody\n"));
+        assert!(is_incomplete("cat <<A <<B\na\nA\n"));
+        assert!(is_incomplete("echo 'abc"));
+        assert!(is_incomplete("echo \"abc"));
+        assert!(is_incomplete("echo abc\\"));
+        assert!(is_incomplete("echo $(date"));
+        assert!(is_incomplete("echo ${x"));
+        assert!(is_incomplete("echo `date"));
+        assert!(!is_incomplete("echo 'a\nb'"));
+
+        // Une ligne terminée par `\` se poursuit sur la suivante
+        assert_eq!(tokens("echo a\\\nb"), ["echo", " ", "ab"]);
+    }
+
+    #[test]
+    fn blanks() {
+        assert_eq!(tokens("echo\ta \t b"), ["echo", " ", "a", " ", " ", " ", "b"]);
+        assert_eq!(tokens("echo '\t'"), ["echo", " ", "'\t'"]);
+        assert_eq!(tokens("  # commentaire\tfin"), [" ", " "]);
+    }
+}
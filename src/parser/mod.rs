@@ -1,9 +1,23 @@
+mod command;
+mod command_list;
+mod incomplete;
 mod lexer;
 mod token;
+#[allow(clippy::module_inception)]
 mod parser;
 mod redirection;
+mod scan;
 mod parsed_command;
+mod pipeline;
+mod word;
 
+pub use command::{Command, IfClause};
+pub use command_list::{AndOrList, CommandList, LogicalOperator};
+pub use lexer::Lexer;
+pub use parsed_command::ParsedCommand;
 pub use parser::Parser;
-pub use redirection::Redirection;
+pub use pipeline::Pipeline;
+pub use redirection::{FileDescriptor, RedirectMode, Redirection};
+pub use scan::{read_backquoted, read_until_closing};
 pub use token::Token;
+pub use word::{is_name, Word, WordPart};
@@ -1,22 +1,34 @@
+use std::fmt;
+
 use crate::parser::redirection::Redirection;
+use crate::parser::word::Word;
 
+/// Affectation `NOM=valeur` placée devant une commande
 #[derive(Debug)]
-pub struct ParsedCommand {
+pub struct Assignment {
     pub name: String,
-    pub args: Vec<String>,
-    pub redirections: Vec<Redirection>,
+    pub value: Word,
 }
 
-impl ParsedCommand {
-    pub fn stdout_redirect(&self) -> Option<&Redirection> {
-        self.redirections
-            .iter()
-            .find(|r| r.is_stdout())
-    }
+/// Commande simple telle que lue : affectations, mots et redirections non
+/// expansés
+#[derive(Debug)]
+pub struct ParsedCommand {
+    pub assignments: Vec<Assignment>,
+    pub words: Vec<Word>,
+    pub redirections: Vec<Redirection<Word>>,
+}
 
-    pub fn stderr_redirect(&self) -> Option<&Redirection> {
-        self.redirections
+impl fmt::Display for ParsedCommand {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let assignments = self
+            .assignments
             .iter()
-            .find(|r| r.is_stderr())
+            .map(|assignment| format!("{}={}", assignment.name, assignment.value));
+        let words = self.words.iter().map(Word::to_string);
+        let redirections = self.redirections.iter().map(Redirection::to_string);
+
+        let text: Vec<String> = assignments.chain(words).chain(redirections).collect();
+        write!(f, "{}", text.join(" "))
     }
-}
\ No newline at end of file
+}
@@ -1,106 +1,538 @@
 use anyhow::{Result, bail};
+use crate::parser::command::{Command, IfClause};
+use crate::parser::command_list::{AndOrList, CommandList, LogicalOperator};
+use crate::parser::incomplete::IncompleteInput;
 use crate::parser::lexer::Lexer;
-use crate::parser::parsed_command::ParsedCommand;
-use crate::parser::redirection::Redirection;
+use crate::parser::parsed_command::{Assignment, ParsedCommand};
+use crate::parser::pipeline::Pipeline;
+use crate::parser::redirection::{FileDescriptor, RedirectMode, Redirection};
+use crate::parser::word::{Word, WordPart};
 use crate::parser::Token;
+use std::collections::VecDeque;
+use std::iter::Peekable;
+use std::vec::IntoIter;
 
-pub struct Parser;
+/// Mots reconnus comme mots réservés en tête de commande
+const RESERVED_WORDS: [&str; 5] = ["if", "then", "elif", "else", "fi"];
+
+/// Analyseur descendant : les listes contiennent des pipelines, dont les
+/// étapes peuvent elles-mêmes contenir des listes (`if ... fi`).
+pub struct Parser {
+    tokens: VecDeque<Token>,
+}
 
 impl Parser {
-    pub fn parse(input: &str) -> Result<ParsedCommand> {
+    pub fn parse(input: &str) -> Result<CommandList> {
         let tokens = Lexer::lex(input)?;
 
-        Self::parse_tokens(tokens)
+        let mut parser = Self {
+            tokens: tokens.into(),
+        };
+        parser.parse_list(&[])
     }
 
-    fn parse_tokens(tokens: Vec<Token>) -> Result<ParsedCommand> {
-        let mut iter = tokens.into_iter().peekable();
+    /// Indique si l'entrée s'arrête au milieu d'une construction et doit
+    /// être complétée par les lignes suivantes (ex: here-document).
+    pub fn is_incomplete(input: &str) -> bool {
+        matches!(Self::parse(input), Err(err) if err.is::<IncompleteInput>())
+    }
 
-        // Skip leading whitespace
-        while matches!(iter.peek(), Some(Token::Whitespace)) {
-            iter.next();
+    /// Lit des listes `&&` / `||` séparées par `;`, `&` ou des retours à la
+    /// ligne, jusqu'à la fin de l'entrée ou l'un des mots réservés
+    /// `terminators` en position de commande (laissé dans le flux).
+    fn parse_list(&mut self, terminators: &[&str]) -> Result<CommandList> {
+        let mut items = Vec::new();
+
+        loop {
+            // Lignes vides, ou ne contenant qu'un commentaire
+            while matches!(self.tokens.front(), Some(Token::Whitespace | Token::Newline)) {
+                self.tokens.pop_front();
+            }
+            if self.tokens.is_empty() || terminators.iter().any(|word| self.at_reserved(word)) {
+                break;
+            }
+
+            let mut list = self.parse_and_or()?;
+            match self.tokens.front() {
+                Some(Token::Background) => list.background = true,
+                Some(Token::Semicolon | Token::Newline) | None => {}
+                Some(token) => bail!("syntax error near unexpected token `{}'", Self::describe(token)),
+            }
+            // Un `;` ou `&` final est autorisé
+            self.tokens.pop_front();
+            items.push(list);
         }
 
-        // Parse command name
-        let name = match iter.next() {
-            Some(Token::Litteral(name)) => name,
-            Some(Token::QuotedString(name, _)) => name,
-            Some(_) => bail!("First token must be a word or quoted string"),
-            None => bail!("No command provided"),
+        Ok(CommandList { items })
+    }
+
+    fn parse_and_or(&mut self) -> Result<AndOrList> {
+        let first = self.parse_pipeline()?;
+        let mut rest = Vec::new();
+
+        loop {
+            let operator = match self.tokens.front() {
+                Some(Token::And) => LogicalOperator::And,
+                Some(Token::Or) => LogicalOperator::Or,
+                _ => break,
+            };
+            self.tokens.pop_front();
+            self.skip_to_operand()?;
+            rest.push((operator, self.parse_pipeline()?));
+        }
+
+        Ok(AndOrList {
+            first,
+            rest,
+            background: false,
+        })
+    }
+
+    fn parse_pipeline(&mut self) -> Result<Pipeline> {
+        let mut commands = vec![self.parse_command()?];
+
+        while matches!(self.tokens.front(), Some(Token::Pipe)) {
+            self.tokens.pop_front();
+            self.skip_to_operand()?;
+            commands.push(self.parse_command()?);
+        }
+
+        Ok(Pipeline { commands })
+    }
+
+    /// Après `|`, `&&` ou `||`, la commande peut se trouver sur une ligne
+    /// suivante ; si l'entrée s'arrête là, elle doit être complétée.
+    fn skip_to_operand(&mut self) -> Result<()> {
+        while matches!(self.tokens.front(), Some(Token::Whitespace | Token::Newline)) {
+            self.tokens.pop_front();
+        }
+        if self.tokens.is_empty() {
+            return Err(IncompleteInput::new("command expected").into());
+        }
+        Ok(())
+    }
+
+    /// Lit une étape de pipeline, puis les blancs qui la suivent. Les mots
+    /// réservés ne sont reconnus qu'ici, en tête de commande.
+    fn parse_command(&mut self) -> Result<Command> {
+        while matches!(self.tokens.front(), Some(Token::Whitespace)) {
+            self.tokens.pop_front();
+        }
+
+        let command = if self.at_reserved("if") {
+            Command::If(self.parse_if()?)
+        } else if let Some(word) = RESERVED_WORDS.iter().find(|word| self.at_reserved(word)) {
+            bail!("syntax error near unexpected token `{}'", word);
+        } else {
+            match self.tokens.front() {
+                Some(token) if Self::ends_command(token) => {
+                    bail!("syntax error near unexpected token `{}'", Self::describe(token))
+                }
+                None => bail!("No command provided"),
+                Some(_) => Command::Simple(Self::parse_simple_command(self.take_command_tokens())?),
+            }
         };
 
-        // Skip whitespace after command name
-        while matches!(iter.peek(), Some(Token::Whitespace)) {
-            iter.next();
+        while matches!(self.tokens.front(), Some(Token::Whitespace)) {
+            self.tokens.pop_front();
         }
+        Ok(command)
+    }
 
-        let mut args: Vec<String> = Vec::new();
-        let mut current_arg = String::new();
-        let mut redirections: Vec<Redirection> = Vec::new();
+    fn parse_if(&mut self) -> Result<IfClause> {
+        let mut branches = Vec::new();
+        let mut else_body = None;
 
-        while let Some(token) = iter.peek() {
-            match token {
-                Token::Redirect { mode, fd } => {
-                    if !current_arg.is_empty() {
-                        args.push(current_arg.clone());
-                        current_arg.clear();
-                    }
+        loop {
+            self.tokens.pop_front(); // `if` ou `elif`
+            let condition = self.parse_list(&["then"])?;
+            self.expect_reserved(&["then"], &condition)?;
+            self.tokens.pop_front();
 
-                    let redirect_mode = mode.clone();
-                    let fd_type = *fd;
-                    iter.next();
+            let body = self.parse_list(&["elif", "else", "fi"])?;
+            let keyword = self.expect_reserved(&["elif", "else", "fi"], &body)?;
+            branches.push((condition, body));
 
-                    while matches!(iter.peek(), Some(Token::Whitespace)) {
-                        iter.next();
-                    }
+            match keyword {
+                "elif" => continue,
+                "else" => {
+                    self.tokens.pop_front();
+                    let body = self.parse_list(&["fi"])?;
+                    self.expect_reserved(&["fi"], &body)?;
+                    else_body = Some(body);
+                }
+                _ => {}
+            }
+            break;
+        }
+        self.tokens.pop_front(); // `fi`
 
-                    match iter.next() {
-                        Some(Token::Litteral(filename)) => {
-                            redirections.push(Redirection::new(fd_type, filename, redirect_mode));
-                        }
-                        Some(Token::QuotedString(filename, _)) => {
-                            redirections.push(Redirection::new(fd_type, filename, redirect_mode));
-                        }
-                        _ => bail!("Expected filename after redirect operator"),
-                    }
+        // Seules des redirections peuvent suivre `fi`
+        let mut redirections = Vec::new();
+        let tokens = self.take_command_tokens();
+        if !Self::is_blank(&tokens) {
+            let trailing = Self::parse_simple_command(tokens)?;
+            let word = trailing
+                .assignments
+                .iter()
+                .map(|assignment| format!("{}={}", assignment.name, assignment.value))
+                .chain(trailing.words.iter().map(Word::to_string))
+                .next();
+            if let Some(word) = word {
+                bail!("syntax error near unexpected token `{}'", word);
+            }
+            redirections = trailing.redirections;
+        }
+
+        Ok(IfClause {
+            branches,
+            else_body,
+            redirections,
+        })
+    }
+
+    /// Vérifie que la liste qui précède n'est pas vide et qu'elle est suivie
+    /// de l'un des mots réservés `words`, qui est retourné ; la fin de
+    /// l'entrée signifie que la commande se poursuit sur les lignes suivantes.
+    fn expect_reserved(&self, words: &[&'static str], list: &CommandList) -> Result<&'static str> {
+        let Some(&word) = words.iter().find(|word| self.at_reserved(word)) else {
+            if self.tokens.is_empty() {
+                let expected = words.last().copied().unwrap_or_default();
+                return Err(IncompleteInput::new(format!("`{}' expected", expected)).into());
+            }
+            bail!("syntax error: `{}' expected", words.join("' or `"));
+        };
+
+        if list.is_empty() {
+            bail!("syntax error near unexpected token `{}'", word);
+        }
+        Ok(word)
+    }
+
+    /// Le prochain token est le mot réservé `word` : un mot non quoté
+    /// formé de ce seul texte
+    fn at_reserved(&self, word: &str) -> bool {
+        matches!(self.tokens.front(), Some(Token::Litteral(text)) if text == word)
+            && !matches!(self.tokens.get(1), Some(Token::Litteral(_) | Token::QuotedString(..)))
+    }
+
+    /// Retire du flux les tokens d'une commande simple, jusqu'au prochain
+    /// opérateur
+    fn take_command_tokens(&mut self) -> Vec<Token> {
+        let end = self
+            .tokens
+            .iter()
+            .position(Self::ends_command)
+            .unwrap_or(self.tokens.len());
+        self.tokens.drain(..end).collect()
+    }
+
+    fn ends_command(token: &Token) -> bool {
+        matches!(
+            token,
+            Token::Pipe | Token::And | Token::Or | Token::Semicolon | Token::Background | Token::Newline
+        )
+    }
+
+    /// Texte d'un token tel qu'affiché dans les erreurs de syntaxe
+    fn describe(token: &Token) -> String {
+        match token {
+            Token::Pipe => "|".to_string(),
+            Token::And => "&&".to_string(),
+            Token::Or => "||".to_string(),
+            Token::Semicolon => ";".to_string(),
+            Token::Background => "&".to_string(),
+            Token::Newline => "newline".to_string(),
+            Token::Litteral(text) | Token::QuotedString(text, _) => text.clone(),
+            Token::Whitespace => " ".to_string(),
+            Token::Redirect { .. } | Token::RedirectAll { .. } | Token::HereDoc { .. } => {
+                "redirection".to_string()
+            }
+        }
+    }
+
+    fn is_blank(tokens: &[Token]) -> bool {
+        tokens.iter().all(|token| matches!(token, Token::Whitespace))
+    }
+
+    fn parse_simple_command(tokens: Vec<Token>) -> Result<ParsedCommand> {
+        let mut iter = tokens.into_iter().peekable();
 
-                    while matches!(iter.peek(), Some(Token::Whitespace)) {
-                        iter.next();
+        let mut words: Vec<Word> = Vec::new();
+        let mut current_word = Word::default();
+        let mut redirections: Vec<Redirection<Word>> = Vec::new();
+
+        while let Some(token) = iter.next() {
+            match token {
+                Token::Redirect { mode, fd } => {
+                    Self::push_word_if_not_empty(&mut words, &mut current_word);
+
+                    let target = Self::parse_redirect_target(&mut iter)?;
+                    // `>& fichier` : ni descripteur ni `-`, équivaut à `&> fichier`
+                    if mode == RedirectMode::Duplicate
+                        && fd == FileDescriptor::STDOUT
+                        && Self::names_file(&target)
+                    {
+                        Self::push_redirect_all(&mut redirections, target, RedirectMode::Overwrite);
+                    } else {
+                        redirections.push(Redirection::new(fd, target, mode));
                     }
                 }
-                Token::Whitespace => {
-                    if !current_arg.is_empty() {
-                        args.push(current_arg.clone());
-                        current_arg.clear();
-                    }
-                    iter.next();
+                Token::HereDoc { fd, body } => {
+                    Self::push_word_if_not_empty(&mut words, &mut current_word);
+                    redirections.push(Redirection::new(fd, body, RedirectMode::HereDoc));
+                }
+                Token::RedirectAll { mode } => {
+                    Self::push_word_if_not_empty(&mut words, &mut current_word);
 
-                    while matches!(iter.peek(), Some(Token::Whitespace)) {
-                        iter.next();
-                    }
+                    let target = Self::parse_redirect_target(&mut iter)?;
+                    Self::push_redirect_all(&mut redirections, target, mode);
                 }
-                Token::Litteral(word) => {
-                    current_arg.push_str(word);
-                    iter.next();
+                Token::Whitespace => {
+                    Self::push_word_if_not_empty(&mut words, &mut current_word);
                 }
-                Token::QuotedString(content, _) => {
-                    current_arg.push_str(content);
-                    iter.next();
+                Token::Litteral(text) => {
+                    current_word.push(WordPart::Unquoted(text));
                 }
-                _ => {
-                    iter.next();
+                Token::QuotedString(text, quote) => {
+                    current_word.push(WordPart::quoted(text, quote));
                 }
+                Token::Pipe
+                | Token::And
+                | Token::Or
+                | Token::Semicolon
+                | Token::Background
+                | Token::Newline => {}
             }
         }
 
-        if !current_arg.is_empty() {
-            args.push(current_arg);
+        Self::push_word_if_not_empty(&mut words, &mut current_word);
+
+        if words.is_empty() && redirections.is_empty() {
+            bail!("No command provided");
+        }
+
+        // Les mots `NOM=valeur` en tête de commande sont des affectations
+        let mut assignments = Vec::new();
+        let mut words = words.into_iter().peekable();
+        while let Some((name, value)) = words.peek().and_then(Word::as_assignment) {
+            words.next();
+            assignments.push(Assignment { name, value });
         }
 
         Ok(ParsedCommand {
-            name,
-            args,
+            assignments,
+            words: words.collect(),
             redirections,
         })
     }
-}
\ No newline at end of file
+
+    /// Lit la cible d'une redirection : le mot qui suit, éventuellement fait
+    /// de plusieurs fragments accolés (ex: `> "out"_$?.txt`)
+    fn parse_redirect_target(iter: &mut Peekable<IntoIter<Token>>) -> Result<Word> {
+        while matches!(iter.peek(), Some(Token::Whitespace)) {
+            iter.next();
+        }
+
+        let mut target = Word::default();
+        while let Some(Token::Litteral(_) | Token::QuotedString(..)) = iter.peek() {
+            match iter.next() {
+                Some(Token::Litteral(text)) => target.push(WordPart::Unquoted(text)),
+                Some(Token::QuotedString(text, quote)) => target.push(WordPart::quoted(text, quote)),
+                _ => unreachable!(),
+            }
+        }
+
+        if target.is_empty() {
+            bail!("Expected filename after redirect operator");
+        }
+        Ok(target)
+    }
+
+    /// `&> cible` équivaut à `> cible 2>&1`
+    fn push_redirect_all(redirections: &mut Vec<Redirection<Word>>, target: Word, mode: RedirectMode) {
+        redirections.push(Redirection::new(FileDescriptor::STDOUT, target, mode));
+        redirections.push(Redirection::new(
+            FileDescriptor::STDERR,
+            Word::literal("1"),
+            RedirectMode::Duplicate,
+        ));
+    }
+
+    /// La cible d'un `>&`, sans expansion à faire, n'est ni un descripteur
+    /// ni `-` : c'est un nom de fichier
+    fn names_file(target: &Word) -> bool {
+        let mut text = String::new();
+        for part in &target.parts {
+            match part {
+                WordPart::Unquoted(part) | WordPart::DoubleQuoted(part) => {
+                    if part.contains(['$', '`']) {
+                        return false;
+                    }
+                    text.push_str(part);
+                }
+                WordPart::SingleQuoted(part) => text.push_str(part),
+            }
+        }
+        text != "-" && !text.chars().all(|c| c.is_ascii_digit())
+    }
+
+    #[inline]
+    fn push_word_if_not_empty(words: &mut Vec<Word>, current_word: &mut Word) {
+        if !current_word.is_empty() {
+            words.push(std::mem::take(current_word));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Liste analysée, réécrite sur une ligne
+    fn parse(input: &str) -> String {
+        Parser::parse(input).unwrap().to_string()
+    }
+
+    fn error(input: &str) -> String {
+        Parser::parse(input).unwrap_err().to_string()
+    }
+
+    /// Nombre d'étapes de chaque pipeline de la première liste
+    fn pipeline_lengths(input: &str) -> Vec<usize> {
+        let list = Parser::parse(input).unwrap();
+        let item = &list.items[0];
+        std::iter::once(&item.first)
+            .chain(item.rest.iter().map(|(_, pipeline)| pipeline))
+            .map(|pipeline| pipeline.commands.len())
+            .collect()
+    }
+
+    #[test]
+    fn pipelines() {
+        assert_eq!(parse("ls -l | grep x | wc -l"), "ls -l | grep x | wc -l;");
+        assert_eq!(pipeline_lengths("ls -l | grep x | wc -l"), [3]);
+        assert_eq!(pipeline_lengths("a|b"), [2]);
+        assert_eq!(pipeline_lengths("echo 'a | b'"), [1]);
+
+        // La commande qui suit `|` peut être sur la ligne suivante
+        assert_eq!(parse("echo a |\n  cat"), "echo a | cat;");
+        assert!(Parser::is_incomplete("echo a |"));
+        assert!(Parser::is_incomplete("echo a | \n"));
+
+        assert_eq!(error("| cat"), "syntax error near unexpected token `|'");
+        assert_eq!(error("echo a | | cat"), "syntax error near unexpected token `|'");
+    }
+
+    #[test]
+    fn lists() {
+        assert_eq!(parse("a; b;c"), "a; b; c;");
+        assert_eq!(parse("a;"), "a;");
+        assert_eq!(parse("a\n\nb\n"), "a; b;");
+        assert_eq!(parse("a && b || c"), "a && b || c;");
+        assert_eq!(parse("a & b"), "a & b;");
+        assert_eq!(pipeline_lengths("a | b && c || d | e"), [2, 1, 2]);
+
+        let list = Parser::parse("a && b || c; d").unwrap();
+        assert_eq!(list.items.len(), 2);
+        let operators: Vec<_> = list.items[0].rest.iter().map(|(operator, _)| *operator).collect();
+        assert_eq!(operators, [LogicalOperator::And, LogicalOperator::Or]);
+
+        // Les lignes vides et les commentaires ne produisent rien
+        assert!(Parser::parse("").unwrap().is_empty());
+        assert!(Parser::parse("  # commentaire\n\n").unwrap().is_empty());
+
+        assert_eq!(parse("a &&\n b"), "a && b;");
+        assert!(Parser::is_incomplete("a ||"));
+        assert!(Parser::is_incomplete("a &&\n"));
+
+        assert_eq!(error(";"), "syntax error near unexpected token `;'");
+        assert_eq!(error("a ;; b"), "syntax error near unexpected token `;'");
+        assert_eq!(error("&& b"), "syntax error near unexpected token `&&'");
+        assert_eq!(error("a || || b"), "syntax error near unexpected token `||'");
+    }
+
+    #[test]
+    fn redirections() {
+        assert_eq!(parse("cmd 2>&1 >out"), "cmd 2>&1 >out;");
+        assert_eq!(parse("cmd >&2 <&- <in"), "cmd >&2 <&- <in;");
+        assert_eq!(parse("cmd > \"out\"_$?.txt"), "cmd >\"out\"_$?.txt;");
+        assert_eq!(parse(">out"), ">out;");
+
+        // `&>` équivaut à `>cible 2>&1`
+        assert_eq!(parse("cmd &>out"), "cmd >out 2>&'1';");
+        assert_eq!(parse("cmd &>>log"), "cmd >>log 2>&'1';");
+        assert_eq!(parse("cmd >& out"), "cmd >out 2>&'1';");
+        assert_eq!(parse("cmd >&'2'"), "cmd >&'2';");
+        assert_eq!(parse("cmd >&$fd"), "cmd >&$fd;");
+        assert_eq!(parse("cmd 2>&-"), "cmd 2>&-;");
+
+        // Les chiffres collés à un fragment quoté terminent le mot (régression)
+        let list = Parser::parse("echo \"a\"2>/dev/null").unwrap();
+        let Command::Simple(command) = &list.items[0].first.commands[0] else {
+            panic!("commande simple attendue");
+        };
+        let words: Vec<String> = command.words.iter().map(Word::to_string).collect();
+        assert_eq!(words, ["echo", "\"a\"2"]);
+        assert_eq!(command.redirections.len(), 1);
+        assert_eq!(command.redirections[0].fd, FileDescriptor::STDOUT);
+        assert_eq!(command.redirections[0].mode, RedirectMode::Overwrite);
+
+        assert_eq!(error("cmd >"), "Expected filename after redirect operator");
+        assert_eq!(error("cmd > | cat"), "Expected filename after redirect operator");
+    }
+
+    #[test]
+    fn heredocs() {
+        let list = Parser::parse("cat <<EOF | wc -l\none\ntwo\nEOF\necho done").unwrap();
+        assert_eq!(list.items.len(), 2);
+        assert_eq!(list.items[0].first.commands.len(), 2);
+
+        let Command::Simple(command) = &list.items[0].first.commands[0] else {
+            panic!("commande simple attendue");
+        };
+        assert_eq!(command.redirections[0].mode, RedirectMode::HereDoc);
+        assert_eq!(command.redirections[0].target.to_string(), "\"one\ntwo\n\"");
+
+        assert_eq!(parse("cat <<< \"a b\""), "cat <<<\"a b\";");
+        assert!(Parser::is_incomplete("cat <<EOF\nbody"));
+        assert!(!Parser::is_incomplete("cat <<EOF\nbody\nEOF"));
+        assert!(!Parser::is_incomplete("echo a; echo b"));
+    }
+
+    #[test]
+    fn if_clauses() {
+        assert_eq!(parse("if true; then echo a; fi"), "if true; then echo a; fi;");
+        assert_eq!(
+            parse("if a; then b; elif c; then d; else e; fi"),
+            "if a; then b; elif c; then d; else e; fi;"
+        );
+        assert_eq!(parse("if a\nthen\n  b\nelse\n  c\nfi"), "if a; then b; else c; fi;");
+        assert_eq!(parse("if a; then b; fi >out | cat && d"), "if a; then b; fi >out | cat && d;");
+
+        let list = Parser::parse("if a; then b; elif c; then d; else e; fi").unwrap();
+        let Command::If(clause) = &list.items[0].first.commands[0] else {
+            panic!("commande if attendue");
+        };
+        assert_eq!(clause.branches.len(), 2);
+        assert!(clause.else_body.is_some());
+
+        // Imbrication, et mots réservés hors position de commande
+        assert_eq!(
+            parse("if if a; then b; fi; then echo if fi; fi"),
+            "if if a; then b; fi; then echo if fi; fi;"
+        );
+        assert_eq!(parse("echo then; fi=1 true"), "echo then; fi=1 true;");
+        assert_eq!(parse("'if' a"), "'if' a;");
+
+        assert!(Parser::is_incomplete("if a; then"));
+        assert!(Parser::is_incomplete("if a; then b;\nelse"));
+        assert!(Parser::is_incomplete("if a; then if b; then c; fi"));
+
+        assert_eq!(error("fi"), "syntax error near unexpected token `fi'");
+        assert_eq!(error("if a; fi"), "syntax error near unexpected token `fi'");
+        assert_eq!(error("if then b; fi"), "syntax error near unexpected token `then'");
+        assert_eq!(error("if a; then fi"), "syntax error near unexpected token `fi'");
+        assert_eq!(error("if a; then b; fi c"), "syntax error near unexpected token `c'");
+    }
+}
@@ -0,0 +1,17 @@
+use std::fmt;
+
+use crate::parser::command::Command;
+
+/// Suite de commandes reliées par `|` : la sortie standard de chaque
+/// commande alimente l'entrée standard de la suivante.
+#[derive(Debug)]
+pub struct Pipeline {
+    pub commands: Vec<Command>,
+}
+
+impl fmt::Display for Pipeline {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let commands: Vec<String> = self.commands.iter().map(Command::to_string).collect();
+        write!(f, "{}", commands.join(" | "))
+    }
+}
@@ -1,43 +1,56 @@
+use std::fmt;
+
+/// Descripteur de fichier visé par une redirection (0 à 9)
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub enum FileDescriptor {
-    Stdout,
-    Stderr,
-}
+pub struct FileDescriptor(u8);
 
 impl FileDescriptor {
+    pub const STDIN: Self = Self(0);
+    pub const STDOUT: Self = Self(1);
+    pub const STDERR: Self = Self(2);
+
+    /// Plus grand descripteur accepté dans une redirection
+    pub const MAX: u8 = 9;
+
     pub fn from_str(s: &str) -> Result<Self, String> {
-        match s {
-            "" | "1" | "stdout" => Ok(Self::Stdout),
-            "2" | "stderr" => Ok(Self::Stderr),
+        match s.parse::<u8>() {
+            Ok(fd) if fd <= Self::MAX && s.len() == 1 => Ok(Self(fd)),
             _ => Err(s.to_string()),
         }
     }
+
+    pub fn index(&self) -> usize {
+        self.0 as usize
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum RedirectMode {
     Overwrite,  // >
     Append,     // >>
+    Input,      // <
+    Duplicate,  // >& et <& (la cible est un descripteur ou `-`)
+    HereDoc,    // << et <<- (la cible est le corps du document)
+    HereString, // <<<
 }
 
+/// Redirection d'un descripteur vers une cible : un mot non expansé à la
+/// sortie du parser, un nom de fichier une fois l'expansion faite.
 #[derive(Debug, Clone)]
-pub struct Redirection {
+pub struct Redirection<T = String> {
     pub fd: FileDescriptor,
-    pub target: String,
+    pub target: T,
     pub mode: RedirectMode,
 }
 
-impl Redirection {
-    pub fn new(fd: FileDescriptor, target: String, mode: RedirectMode) -> Self {
+impl<T> Redirection<T> {
+    pub fn new(fd: FileDescriptor, target: T, mode: RedirectMode) -> Self {
         Self { fd, target, mode }
     }
 
-    pub fn is_stdout(&self) -> bool {
-        self.fd == FileDescriptor::Stdout
-    }
-
-    pub fn is_stderr(&self) -> bool {
-        self.fd == FileDescriptor::Stderr
+    /// Construit la même redirection avec une cible transformée
+    pub fn try_map<U, E>(&self, f: impl FnOnce(&T) -> Result<U, E>) -> Result<Redirection<U>, E> {
+        Ok(Redirection::new(self.fd, f(&self.target)?, self.mode))
     }
 
     pub fn is_append(&self) -> bool {
@@ -47,4 +60,24 @@ impl Redirection {
     pub fn is_overwrite(&self) -> bool {
         matches!(self.mode, RedirectMode::Overwrite)
     }
-}
\ No newline at end of file
+}
+
+impl<T: fmt::Display> fmt::Display for Redirection<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (default_fd, operator) = match self.mode {
+            RedirectMode::Overwrite => (FileDescriptor::STDOUT, ">"),
+            RedirectMode::Append => (FileDescriptor::STDOUT, ">>"),
+            RedirectMode::Input => (FileDescriptor::STDIN, "<"),
+            RedirectMode::Duplicate if self.fd == FileDescriptor::STDIN => (FileDescriptor::STDIN, "<&"),
+            RedirectMode::Duplicate => (FileDescriptor::STDOUT, ">&"),
+            // Le corps du document n'est pas réaffiché
+            RedirectMode::HereDoc => return write!(f, "<<"),
+            RedirectMode::HereString => (FileDescriptor::STDIN, "<<<"),
+        };
+
+        if self.fd != default_fd {
+            write!(f, "{}", self.fd.0)?;
+        }
+        write!(f, "{}{}", operator, self.target)
+    }
+}
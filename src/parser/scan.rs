@@ -0,0 +1,74 @@
+use std::iter::Peekable;
+use std::str::Chars;
+
+/// Lit le texte jusqu'au `close` correspondant à un `open` déjà consommé,
+/// en tenant compte de l'imbrication, des quotes et des échappements.
+///
+/// Le texte est retourné tel quel, sans le délimiteur fermant ; `None` si
+/// l'entrée se termine avant.
+pub fn read_until_closing(chars: &mut Peekable<Chars>, open: char, close: char) -> Option<String> {
+    let mut content = String::new();
+    let mut depth = 0;
+
+    while let Some(ch) = chars.next() {
+        match ch {
+            '\\' => {
+                content.push(ch);
+                content.push(chars.next()?);
+            }
+            '\'' => {
+                content.push(ch);
+                loop {
+                    let c = chars.next()?;
+                    content.push(c);
+                    if c == '\'' {
+                        break;
+                    }
+                }
+            }
+            '"' => {
+                content.push(ch);
+                loop {
+                    let c = chars.next()?;
+                    content.push(c);
+                    match c {
+                        '\\' => content.push(chars.next()?),
+                        '"' => break,
+                        _ => {}
+                    }
+                }
+            }
+            c if c == close && depth == 0 => return Some(content),
+            c => {
+                if c == open {
+                    depth += 1;
+                } else if c == close {
+                    depth -= 1;
+                }
+                content.push(c);
+            }
+        }
+    }
+
+    None
+}
+
+/// Lit le contenu d'une substitution `` `...` `` jusqu'au backquote fermant
+/// non échappé, le backquote ouvrant étant déjà consommé. Les `\` sont
+/// conservés ; `None` si l'entrée se termine avant.
+pub fn read_backquoted(chars: &mut Peekable<Chars>) -> Option<String> {
+    let mut content = String::new();
+
+    while let Some(ch) = chars.next() {
+        match ch {
+            '`' => return Some(content),
+            '\\' => {
+                content.push(ch);
+                content.push(chars.next()?);
+            }
+            ch => content.push(ch),
+        }
+    }
+
+    None
+}
@@ -1,8 +1,20 @@
 pub(crate) use crate::parser::redirection::{FileDescriptor, RedirectMode};
+use crate::parser::word::Word;
 
 pub enum Token {
     Litteral(String),
     Whitespace,
     QuotedString(String, char),
     Redirect { mode: RedirectMode, fd: FileDescriptor },
+    /// `&>` et `&>>` : stdout et stderr vers la même cible
+    RedirectAll { mode: RedirectMode },
+    /// `<<` et `<<-` : le corps est lu sur les lignes suivantes
+    HereDoc { fd: FileDescriptor, body: Word },
+    Pipe,
+    And,
+    Or,
+    Semicolon,
+    /// `&` : la liste qui précède s'exécute en arrière-plan
+    Background,
+    Newline,
 }
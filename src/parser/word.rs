@@ -0,0 +1,86 @@
+use std::fmt;
+
+/// Indique si `s` est un nom de variable valide (`[A-Za-z_][A-Za-z0-9_]*`)
+pub fn is_name(s: &str) -> bool {
+    let mut chars = s.chars();
+    matches!(chars.next(), Some(c) if c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// Fragment d'un mot, selon le contexte de quotes dans lequel il a été lu
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WordPart {
+    /// Texte hors quotes : soumis à toutes les expansions
+    Unquoted(String),
+    /// Texte entre quotes simples ou caractère échappé : jamais expansé
+    SingleQuoted(String),
+    /// Texte entre quotes doubles : expansion des `$` uniquement
+    DoubleQuoted(String),
+}
+
+impl WordPart {
+    /// Construit un fragment à partir du contenu d'un `Token::QuotedString`
+    pub fn quoted(text: String, quote: char) -> Self {
+        match quote {
+            '"' => Self::DoubleQuoted(text),
+            _ => Self::SingleQuoted(text),
+        }
+    }
+}
+
+/// Mot de la ligne de commande tel qu'écrit, avant expansion
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Word {
+    pub parts: Vec<WordPart>,
+}
+
+impl Word {
+    /// Mot littéral, qui ne subira aucune expansion
+    pub fn literal(text: &str) -> Self {
+        Self {
+            parts: vec![WordPart::SingleQuoted(text.to_string())],
+        }
+    }
+
+    pub fn push(&mut self, part: WordPart) {
+        self.parts.push(part);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.parts.is_empty()
+    }
+
+    /// Décompose un mot de la forme `NOM=valeur` (le nom hors quotes)
+    pub fn as_assignment(&self) -> Option<(String, Word)> {
+        let Some(WordPart::Unquoted(first)) = self.parts.first() else {
+            return None;
+        };
+
+        let (name, value) = first.split_once('=')?;
+        if !is_name(name) {
+            return None;
+        }
+
+        let mut parts = Vec::with_capacity(self.parts.len());
+        if !value.is_empty() {
+            parts.push(WordPart::Unquoted(value.to_string()));
+        }
+        parts.extend(self.parts[1..].iter().cloned());
+
+        Some((name.to_string(), Word { parts }))
+    }
+}
+
+/// Réécrit le mot tel qu'il a été saisi (affichage des jobs)
+impl fmt::Display for Word {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for part in &self.parts {
+            match part {
+                WordPart::Unquoted(text) => write!(f, "{}", text)?,
+                WordPart::SingleQuoted(text) => write!(f, "'{}'", text)?,
+                WordPart::DoubleQuoted(text) => write!(f, "\"{}\"", text)?,
+            }
+        }
+        Ok(())
+    }
+}
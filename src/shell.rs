@@ -1,13 +1,28 @@
 use std::io::{self, Read, Write};
 use std::os::unix::io::AsRawFd;
-use termios::{tcsetattr, Termios, ECHO, ICANON, TCSANOW};
 
+use crate::command::CommandHandler;
 use crate::commands::CommandRegistry;
-use crate::execution::RedirectionManager;
-use crate::parser::{Parser, Redirection};
-use crate::utils::path::find_executables_with_prefix;
-use std::fs::OpenOptions;
+use crate::execution::process::{self, Pid};
+use crate::execution::{
+    create_pipe, signals, terminal, Job, JobState, JobTable, ProcessSubstitution, RedirectionManager,
+    SubstitutionKind, Terminal,
+};
+use crate::expansion::{ExpandedCommand, Expander, FatalExpansion};
+use crate::parser::{
+    self, AndOrList, CommandList, FileDescriptor, IfClause, LogicalOperator, ParsedCommand, Parser, Pipeline,
+    Redirection,
+};
+use crate::utils::error::{describe, strerror};
+use crate::utils::lines::LineReader;
+use crate::utils::path::{find_executable_in_path, find_executables_with_prefix, is_executable};
+use std::fs::File;
+use std::os::unix::process::CommandExt;
+use std::path::{Path, PathBuf};
 use std::process::Command;
+use crate::variables::Variables;
+use crate::options::ShellOptions;
+use std::rc::Rc;
 
 // Constantes pour les codes de caractères spéciaux
 const CHAR_NEWLINE: u8 = b'\n';
@@ -18,206 +33,1083 @@ const CHAR_BACKSPACE: u8 = 8;
 const CHAR_PRINTABLE_MIN: u8 = 32;
 const CHAR_PRINTABLE_MAX: u8 = 127;
 
+// Invites affichées avant chaque ligne
+const PROMPT: &str = "$ ";
+const CONTINUATION_PROMPT: &str = "> ";
+
 // Constantes pour les séquences d'échappement
 const ESCAPE_BELL: &str = "\x07";
 const ESCAPE_ERASE_CHAR: &str = "\x08 \x08";
 
 pub struct Shell {
-    command_registry: CommandRegistry,
-    original_termios: Option<Termios>,
+    command_registry: Rc<CommandRegistry>,
+    terminal: Terminal,
     last_autocomplete_input: Option<String>,
+    last_status: i32,
+    variables: Variables,
+    options: ShellOptions,
+    /// PID du shell (`$$`), inchangé dans les sous-shells
+    pid: u32,
+    /// Nom du shell ou du script (`$0`)
+    script_name: String,
+    /// Paramètres positionnels (`$1`, `$2`...)
+    positional_params: Vec<String>,
+    /// PID de la dernière commande lancée en arrière-plan (`$!`)
+    last_background_pid: Option<Pid>,
+    /// Dernier argument de la commande précédente (`$_`)
+    last_argument: String,
+    /// Le shell lit ses commandes depuis un terminal : invite, édition de la
+    /// ligne et contrôle des jobs
+    interactive: bool,
+    /// `-e` : quitter dès qu'une commande échoue
+    errexit: bool,
+    /// `-x` : afficher chaque commande sur stderr avant de l'exécuter
+    xtrace: bool,
+    /// Nombre de conditions de `if` en cours d'évaluation : leurs échecs ne
+    /// font pas quitter le shell avec `-e`
+    condition_depth: usize,
+    /// Une erreur d'expansion fatale a interrompu la ligne de commande : le
+    /// reste de la ligne et du fichier lu n'est pas exécuté
+    aborted: bool,
+    /// Fichier en cours d'exécution et ligne de la commande courante, pour
+    /// situer les messages d'erreur
+    location: Option<(String, usize)>,
+    /// Chaque pipeline est lancé dans son propre groupe de processus et
+    /// peut être suspendu, repris ou passé en arrière-plan ; désactivé dans
+    /// les sous-shells
+    job_control: bool,
+    /// Groupe de processus du shell, auquel le terminal est rendu
+    pgid: Pid,
+    jobs: JobTable,
 }
 
 impl Shell {
     pub fn new() -> Self {
-        let command_registry = CommandRegistry::new();
+        let command_registry = Rc::new(CommandRegistry::new());
 
         Self {
             command_registry,
-            original_termios: None,
+            terminal: Terminal::new(),
             last_autocomplete_input: None,
+            last_status: 0,
+            variables: Variables::new(),
+            options: ShellOptions::default(),
+            pid: std::process::id(),
+            script_name: std::env::args().next().unwrap_or_else(|| "shell".to_string()),
+            positional_params: Vec::new(),
+            last_background_pid: None,
+            last_argument: String::new(),
+            interactive: terminal::is_terminal(),
+            errexit: false,
+            xtrace: false,
+            condition_depth: 0,
+            aborted: false,
+            location: None,
+            job_control: terminal::is_terminal(),
+            pgid: unsafe { libc::getpgrp() },
+            jobs: JobTable::default(),
         }
     }
 
-    pub fn run(&mut self) {
-        self.enable_raw_mode().unwrap();
+    /// Lit et exécute les commandes jusqu'à la fin de l'entrée, et retourne
+    /// le code de sortie du shell. En mode interactif, seul `exit` termine.
+    pub fn run(&mut self) -> i32 {
+        if !self.interactive {
+            return self.execute_lines(&mut LineReader::new(libc::STDIN_FILENO), None);
+        }
+
+        'prompt: loop {
+            self.notify_jobs();
+            // Un Ctrl-C reçu pendant la commande précédente ne vaut pas pour
+            // la ligne suivante
+            signals::take_interrupt();
+            self.terminal.enter_raw_mode().unwrap();
+
+            let Some(mut input) = self.read_line(PROMPT) else {
+                continue;
+            };
+
+            // Lignes de continuation (corps des here-documents...)
+            while Parser::is_incomplete(&input) {
+                let Some(line) = self.read_line(CONTINUATION_PROMPT) else {
+                    continue 'prompt;
+                };
+                input.push('\n');
+                input.push_str(&line);
+            }
+
+            // Les commandes lancées retrouvent le terminal tel qu'il était
+            // au démarrage du shell
+            self.terminal.restore().unwrap();
+            if !input.trim().is_empty() {
+                self.execute_command(&input);
+            }
+        }
+    }
+
+    /// Exécute le script `path` et retourne le code de retour de sa
+    /// dernière commande (127 s'il est introuvable, 126 s'il est illisible)
+    pub fn run_script(&mut self, path: &str) -> i32 {
+        match self.source_file(Path::new(path)) {
+            Ok(status) => status,
+            Err(err) => {
+                eprintln!("shell: {}: {}", path, strerror(&err));
+                match err.kind() {
+                    io::ErrorKind::NotFound => process::STATUS_NOT_FOUND,
+                    _ => process::STATUS_NOT_EXECUTABLE,
+                }
+            }
+        }
+    }
+
+    /// Exécute le fichier `path` ligne à ligne dans le shell courant ; les
+    /// messages d'erreur indiquent le fichier et la ligne. Retourne le code
+    /// de retour de sa dernière commande.
+    pub fn source_file(&mut self, path: &Path) -> io::Result<i32> {
+        let file = File::open(path)?;
+        if file.metadata()?.is_dir() {
+            return Err(io::Error::other("Is a directory"));
+        }
+
+        let name = path.to_string_lossy();
+        Ok(self.execute_lines(&mut LineReader::new(file.as_raw_fd()), Some(&name)))
+    }
+
+    /// Exécute les commandes lues ligne à ligne, sans invite ; une commande
+    /// sur plusieurs lignes (here-document, `|` final...) est lue en entier.
+    /// Retourne le code de retour de la dernière commande.
+    fn execute_lines(&mut self, reader: &mut LineReader, name: Option<&str>) -> i32 {
+        let outer_location = self.location.take();
+        let mut input: Option<String> = None;
+        let mut line_number = 0;
+        let mut first_line = 0;
 
         loop {
-            let mut input = String::new();
+            let line = match reader.next_line() {
+                Ok(Some(line)) => line,
+                Ok(None) => break,
+                Err(err) => {
+                    eprintln!("read error: {}", strerror(&err));
+                    break;
+                }
+            };
+            line_number += 1;
 
-            print!("$ ");
-            io::stdout().flush().unwrap();
+            let command = match input.take() {
+                Some(pending) => pending + "\n" + &line,
+                None => {
+                    first_line = line_number;
+                    line
+                }
+            };
+            if Parser::is_incomplete(&command) {
+                input = Some(command);
+                continue;
+            }
+
+            if !command.trim().is_empty() {
+                self.location = name.map(|name| (name.to_string(), first_line));
+                self.execute_command(&command);
+            }
+            if self.aborted {
+                break;
+            }
+        }
 
-            // Boucle de lecture caractère par caractère
-            loop {
-                let mut buffer = [0u8; 1];
-                io::stdin().read_exact(&mut buffer).unwrap();
+        // Construction non terminée en fin de fichier : erreur de syntaxe
+        if let Some(command) = input {
+            self.location = name.map(|name| (name.to_string(), first_line));
+            self.execute_command(&command);
+        }
 
-                match buffer[0] {
-                    CHAR_NEWLINE | CHAR_CARRIAGE_RETURN => {
-                        // Enter : fin de saisie
-                        println!();
-                        self.last_autocomplete_input = None;
-                        break;
-                    }
-                    CHAR_TAB => {
-                        // Tab : autocompletion
-                        self.handle_autocomplete(&mut input);
-                    }
-                    CHAR_BACKSPACE_DELETE | CHAR_BACKSPACE => {
-                        // Backspace (127 sur Linux, 8 sur certains systèmes)
-                        if !input.is_empty() {
-                            input.pop();
-                            print!("{}", ESCAPE_ERASE_CHAR); // Efface visuellement
-                            io::stdout().flush().unwrap();
-                        }
+        self.location = outer_location;
+        self.last_status
+    }
+
+    /// Affiche une erreur d'expansion et retourne le code de retour de la
+    /// commande. Une erreur fatale termine un shell non interactif et
+    /// interrompt sinon la ligne de commande en cours.
+    fn expansion_failed(&mut self, err: anyhow::Error) -> i32 {
+        eprintln!("{}", self.located(&err));
+        if err.is::<FatalExpansion>() {
+            if !self.interactive {
+                std::process::exit(1);
+            }
+            self.aborted = true;
+        }
+        1
+    }
+
+    /// Préfixe `message` du fichier et de la ligne en cours d'exécution
+    fn located(&self, message: impl std::fmt::Display) -> String {
+        match &self.location {
+            Some((file, line)) => format!("{}: line {}: {}", file, line, message),
+            None => message.to_string(),
+        }
+    }
+
+    /// Place le shell dans son propre groupe de processus, au premier plan
+    /// du terminal, et le protège des signaux de contrôle des jobs
+    fn init_job_control(&mut self) {
+        signals::ignore_job_control_signals();
+        // Échoue si le shell est leader de session : il a déjà son groupe
+        let _ = signals::set_process_group(0, 0);
+        self.pgid = unsafe { libc::getpgrp() };
+        let _ = terminal::set_foreground(self.pgid);
+    }
+
+    /// Signale les jobs terminés ou suspendus depuis la dernière invite
+    fn notify_jobs(&mut self) {
+        self.jobs.reap();
+        for line in self.jobs.notifications() {
+            eprintln!("{}", line);
+        }
+    }
+
+    /// Affiche `prompt` puis lit une ligne caractère par caractère.
+    ///
+    /// Retourne `None` si la saisie est abandonnée par Ctrl-C : la ligne est
+    /// oubliée et `$?` vaut 130, comme pour une commande interrompue.
+    fn read_line(&mut self, prompt: &str) -> Option<String> {
+        let mut input = String::new();
+
+        print!("{}", prompt);
+        io::stdout().flush().unwrap();
+
+        // Boucle de lecture caractère par caractère
+        loop {
+            let byte = match Self::read_byte() {
+                Err(err) if err.kind() == io::ErrorKind::Interrupted => {
+                    if signals::take_interrupt() {
+                        println!("^C");
                         self.last_autocomplete_input = None;
+                        self.last_status = 128 + libc::SIGINT;
+                        return None;
                     }
-                    c if c >= CHAR_PRINTABLE_MIN && c < CHAR_PRINTABLE_MAX => {
-                        // Caractère imprimable
-                        let ch = c as char;
-                        input.push(ch);
-                        print!("{}", ch);
+                    continue;
+                }
+                // Fin de l'entrée (`-i` hors d'un terminal) : le shell se termine
+                Err(err) if err.kind() == io::ErrorKind::UnexpectedEof => {
+                    println!();
+                    let _ = self.terminal.restore();
+                    std::process::exit(self.last_status);
+                }
+                result => result.unwrap(),
+            };
+
+            match byte {
+                CHAR_NEWLINE | CHAR_CARRIAGE_RETURN => {
+                    // Enter : fin de saisie
+                    println!();
+                    self.last_autocomplete_input = None;
+                    break;
+                }
+                CHAR_TAB => {
+                    // Tab : autocompletion
+                    self.handle_autocomplete(&mut input);
+                }
+                CHAR_BACKSPACE_DELETE | CHAR_BACKSPACE => {
+                    // Backspace (127 sur Linux, 8 sur certains systèmes)
+                    if !input.is_empty() {
+                        input.pop();
+                        print!("{}", ESCAPE_ERASE_CHAR); // Efface visuellement
                         io::stdout().flush().unwrap();
-                        self.last_autocomplete_input = None;
-                    }
-                    _ => {
-                        // Ignorer les autres caractères (séquences escape, etc.)
                     }
+                    self.last_autocomplete_input = None;
+                }
+                c if (CHAR_PRINTABLE_MIN..CHAR_PRINTABLE_MAX).contains(&c) => {
+                    // Caractère imprimable
+                    let ch = c as char;
+                    input.push(ch);
+                    print!("{}", ch);
+                    io::stdout().flush().unwrap();
+                    self.last_autocomplete_input = None;
+                }
+                _ => {
+                    // Ignorer les autres caractères (séquences escape, etc.)
                 }
             }
+        }
 
-            if !input.trim().is_empty() {
-                if !self.execute_command(&input) {
-                    break;
-                }
+        Some(input)
+    }
+
+    /// Lit un octet de l'entrée standard ; contrairement à `read_exact`,
+    /// une lecture interrompue par un signal est rapportée
+    fn read_byte() -> io::Result<u8> {
+        let mut buffer = [0u8; 1];
+        match io::stdin().read(&mut buffer)? {
+            0 => Err(io::ErrorKind::UnexpectedEof.into()),
+            _ => Ok(buffer[0]),
+        }
+    }
+
+    pub fn execute_command(&mut self, input: &str) -> i32 {
+        self.aborted = false;
+        let list = match Parser::parse(input) {
+            Ok(list) => list,
+            Err(err) => {
+                eprintln!("{}", self.located(format_args!("Error parsing command: {}", err)));
+                self.last_status = process::STATUS_SYNTAX_ERROR;
+                return self.last_status;
+            }
+        };
+
+        for and_or in &list.items {
+            if self.aborted {
+                break;
+            }
+            self.execute_and_or(and_or);
+            // Les jobs terminés sont récupérés entre deux commandes, même sans
+            // invite pour le faire (script, `-c`, `source`...). Pas dans
+            // `execute_list` : le corps d'un `if` s'exécute avant que ses
+            // substitutions de processus ne soient attendues.
+            self.jobs.reap();
+        }
+        self.last_status
+    }
+
+    /// Exécute les éléments d'une liste et retourne le code de retour du
+    /// dernier
+    fn execute_list(&mut self, list: &CommandList) -> i32 {
+        for and_or in &list.items {
+            if self.aborted {
+                break;
             }
+            self.execute_and_or(and_or);
         }
+        self.last_status
+    }
+
+    /// Exécute `script` dans un sous-shell et retourne ce qu'il a écrit sur
+    /// stdout, sans les retours à la ligne finaux (`$(...)`).
+    pub fn capture_output(&mut self, script: &str) -> anyhow::Result<String> {
+        let (mut reader, writer) = create_pipe()?;
 
-        self.disable_raw_mode().unwrap();
+        let Some(pid) = process::fork()? else {
+            self.enter_subshell(None);
+            drop(reader);
+            if let Err(err) = Self::attach_stdio(None, Some(writer)) {
+                eprintln!("{}", strerror(&err));
+                process::exit_child(1);
+            }
+            let status = self.execute_command(script);
+            process::exit_child(status);
+        };
+
+        drop(writer);
+        let mut output = Vec::new();
+        let read = reader.read_to_end(&mut output);
+        self.last_status = process::wait_for(pid)?;
+        read?;
+
+        let mut output = String::from_utf8_lossy(&output).into_owned();
+        output.truncate(output.trim_end_matches('\n').len());
+        Ok(output)
     }
 
-    pub fn execute_command(&self, input: &str) -> bool {
-        let command = match Parser::parse(input) {
-            Ok(command) => command,
+    /// Lance `script` dans un sous-shell relié au shell par un pipe : sa
+    /// sortie pour `<(...)`, son entrée pour `>(...)`. Les extrémités des
+    /// substitutions précédentes de la même commande (`others`) sont fermées
+    /// dans l'enfant, pour que leurs lecteurs voient EOF.
+    pub fn spawn_process_substitution(
+        &mut self,
+        script: &str,
+        kind: SubstitutionKind,
+        others: &[ProcessSubstitution],
+    ) -> anyhow::Result<ProcessSubstitution> {
+        let (reader, writer) = create_pipe()?;
+        let (child_end, shell_end) = match kind {
+            SubstitutionKind::Input => (writer, reader),
+            SubstitutionKind::Output => (reader, writer),
+        };
+
+        let Some(pid) = process::fork()? else {
+            self.enter_subshell(None);
+            drop(shell_end);
+            for other in others {
+                unsafe { libc::close(other.raw_fd()) };
+            }
+
+            let (stdin, stdout) = match kind {
+                SubstitutionKind::Input => (None, Some(child_end)),
+                SubstitutionKind::Output => (Some(child_end), None),
+            };
+            if let Err(err) = Self::attach_stdio(stdin, stdout) {
+                eprintln!("{}", strerror(&err));
+                process::exit_child(1);
+            }
+            let status = self.execute_command(script);
+            process::exit_child(status);
+        };
+
+        drop(child_end);
+        self.last_background_pid = Some(pid);
+        Ok(ProcessSubstitution::new(pid, shell_end)?)
+    }
+
+    /// Prépare un processus fils issu de `fork` qui exécute des commandes du
+    /// shell : placé dans le groupe `pgid` s'il forme un job, et toujours
+    /// sans contrôle des jobs. Comme toute commande lancée par le shell, il
+    /// retrouve le comportement par défaut de Ctrl-C.
+    fn enter_subshell(&mut self, pgid: Option<Pid>) {
+        if let Some(pgid) = pgid {
+            let _ = signals::set_process_group(0, pgid);
+        }
+        signals::restore_default_signals(pgid.is_some());
+        self.job_control = false;
+        self.interactive = false;
+    }
+
+    /// Code de retour de la dernière commande exécutée (`$?`)
+    pub fn last_status(&self) -> i32 {
+        self.last_status
+    }
+
+    pub fn registry(&self) -> &CommandRegistry {
+        &self.command_registry
+    }
+
+    pub fn variables(&self) -> &Variables {
+        &self.variables
+    }
+
+    pub fn variables_mut(&mut self) -> &mut Variables {
+        &mut self.variables
+    }
+
+    pub fn options(&self) -> &ShellOptions {
+        &self.options
+    }
+
+    pub fn options_mut(&mut self) -> &mut ShellOptions {
+        &mut self.options
+    }
+
+    pub fn pid(&self) -> u32 {
+        self.pid
+    }
+
+    pub fn script_name(&self) -> &str {
+        &self.script_name
+    }
+
+    pub fn positional_params(&self) -> &[String] {
+        &self.positional_params
+    }
+
+    pub fn set_script_name(&mut self, name: String) {
+        self.script_name = name;
+    }
+
+    pub fn set_positional_params(&mut self, params: Vec<String>) {
+        self.positional_params = params;
+    }
+
+    /// Active le mode interactif (terminal, ou `-i`) : Ctrl-C ne termine
+    /// plus le shell, et le contrôle des jobs est mis en place si l'entrée
+    /// est un terminal
+    pub fn set_interactive(&mut self, interactive: bool) {
+        self.interactive = interactive;
+        self.job_control = interactive && terminal::is_terminal();
+
+        if self.interactive {
+            signals::install_interrupt_handlers();
+        }
+        if self.job_control {
+            self.init_job_control();
+        }
+    }
+
+    pub fn is_interactive(&self) -> bool {
+        self.interactive
+    }
+
+    pub fn set_errexit(&mut self, errexit: bool) {
+        self.errexit = errexit;
+    }
+
+    pub fn set_xtrace(&mut self, xtrace: bool) {
+        self.xtrace = xtrace;
+    }
+
+    pub fn last_background_pid(&self) -> Option<Pid> {
+        self.last_background_pid
+    }
+
+    pub fn last_argument(&self) -> &str {
+        &self.last_argument
+    }
+
+    pub fn has_job_control(&self) -> bool {
+        self.job_control
+    }
+
+    pub fn jobs(&self) -> &JobTable {
+        &self.jobs
+    }
+
+    pub fn jobs_mut(&mut self) -> &mut JobTable {
+        &mut self.jobs
+    }
+
+    /// Relance le job `id` au premier plan (`fg`) et attend qu'il se termine
+    /// ou soit de nouveau suspendu
+    pub fn foreground_job(&mut self, id: usize) -> anyhow::Result<i32> {
+        let Some(job) = self.jobs.remove(id) else {
+            anyhow::bail!("%{}: no such job", id);
+        };
+        Ok(self.wait_foreground(job))
+    }
+
+    /// Lettres des options actives (`$-`)
+    pub fn option_flags(&self) -> String {
+        let mut flags = String::new();
+        if self.errexit {
+            flags.push('e');
+        }
+        if self.interactive {
+            flags.push('i');
+        }
+        if self.job_control {
+            flags.push('m');
+        }
+        if self.xtrace {
+            flags.push('x');
+        }
+        flags
+    }
+
+    /// Exécute une liste `&&` / `||` et retourne le code de retour du
+    /// dernier pipeline exécuté ; une liste terminée par `&` est lancée en
+    /// arrière-plan sans être attendue.
+    fn execute_and_or(&mut self, and_or: &AndOrList) -> i32 {
+        if and_or.background {
+            return self.execute_background(and_or);
+        }
+        self.run_and_or(and_or)
+    }
+
+    fn run_and_or(&mut self, and_or: &AndOrList) -> i32 {
+        let mut status = self.execute_pipeline(&and_or.first);
+        // Avec `-e`, seul l'échec du dernier pipeline de la liste fait
+        // quitter le shell : les autres servent de condition
+        let mut last_ran = and_or.rest.is_empty();
+
+        for (index, (operator, pipeline)) in and_or.rest.iter().enumerate() {
+            if self.aborted {
+                break;
+            }
+            let should_run = match operator {
+                LogicalOperator::And => status == 0,
+                LogicalOperator::Or => status != 0,
+            };
+
+            if should_run {
+                status = self.execute_pipeline(pipeline);
+                last_ran = index == and_or.rest.len() - 1;
+            }
+        }
+
+        if self.errexit && status != 0 && last_ran && self.condition_depth == 0 {
+            std::process::exit(status);
+        }
+        status
+    }
+
+    /// Lance une liste en arrière-plan comme un nouveau job : un pipeline
+    /// seul directement, une liste `&&` / `||` dans un sous-shell.
+    fn execute_background(&mut self, and_or: &AndOrList) -> i32 {
+        let job = if and_or.rest.is_empty() {
+            // Les substitutions de processus seront récupérées avec les jobs
+            self.launch_job(&and_or.first).0
+        } else {
+            self.spawn_subshell_job(and_or)
+        };
+
+        let last_pid = job.last_pid();
+        let id = self.jobs.insert(job);
+        if let Some(pid) = last_pid {
+            self.last_background_pid = Some(pid);
+            if self.interactive {
+                eprintln!("[{}] {}", id, pid);
+            }
+        }
+
+        self.last_status = 0;
+        0
+    }
+
+    /// Exécute une liste `&&` / `||` dans un sous-shell formant un job
+    fn spawn_subshell_job(&mut self, and_or: &AndOrList) -> Job {
+        let mut job = Job::new(and_or.to_string());
+        let pgid = self.job_control.then_some(0);
+
+        let process = self.fork_subshell(None, None, pgid, |shell| shell.run_and_or(and_or));
+        job.add_process(process);
+        job
+    }
+
+    /// Donne le terminal au job, le relance s'il était suspendu, et attend
+    /// qu'il se termine ou soit suspendu ; un job suspendu rejoint la table
+    /// des jobs.
+    fn wait_foreground(&mut self, mut job: Job) -> i32 {
+        let pgid = job.pgid().filter(|_| self.job_control);
+        if let Some(pgid) = pgid {
+            let _ = self.terminal.give_to(pgid, job.terminal_modes());
+        }
+        if job.state() == JobState::Stopped {
+            if let Err(err) = job.resume() {
+                eprintln!("fg: {}", strerror(&err));
+            }
+        }
+
+        job.wait();
+        if pgid.is_some() {
+            // Un job suspendu retrouvera ses paramètres (éditeur en plein
+            // écran...) lorsqu'il sera repris
+            match self.terminal.reclaim(self.pgid) {
+                Ok(modes) => job.set_terminal_modes(modes),
+                Err(err) => eprintln!("{}", err),
+            }
+        }
+
+        let status = job.status();
+        if job.interrupted() {
+            // La ligne où `^C` a été tapé est terminée
+            eprintln!();
+        }
+        if job.state() == JobState::Stopped {
+            let id = self.jobs.insert(job);
+            eprintln!();
+            eprintln!("{}", self.jobs.format(id, false));
+            self.jobs.mark_reported(id);
+        }
+        status
+    }
+
+    /// Exécute un pipeline et mémorise son code de retour
+    fn execute_pipeline(&mut self, pipeline: &Pipeline) -> i32 {
+        let status = match &pipeline.commands[..] {
+            [parser::Command::Simple(command)] => self.execute_simple_command(command),
+            [parser::Command::If(clause)] => self.execute_if(clause),
+            _ => self.execute_multi_stage_pipeline(pipeline),
+        };
+
+        self.last_status = status;
+        status
+    }
+
+    /// Exécute le corps de la première branche dont la condition réussit,
+    /// ou celui du `else` ; sans branche exécutée, le code de retour est 0.
+    /// Les redirections placées après `fi` s'appliquent au shell le temps
+    /// de la commande.
+    fn execute_if(&mut self, clause: &IfClause) -> i32 {
+        let mut expander = Expander::new(self);
+        let redirections = expander.expand_redirections(&clause.redirections);
+        let substitutions = expander.into_substitutions();
+
+        let status = match redirections {
+            Ok(redirections) => self.execute_if_redirected(clause, &redirections),
+            Err(err) => self.expansion_failed(err),
+        };
+
+        // Les substitutions lancées avant une erreur sont aussi attendues
+        for pid in Self::close_substitutions(substitutions) {
+            Self::wait_child(pid);
+        }
+        status
+    }
+
+    /// Applique les redirections au shell, exécute la branche choisie, puis
+    /// rétablit les descripteurs d'origine
+    fn execute_if_redirected(&mut self, clause: &IfClause, redirections: &[Redirection]) -> i32 {
+        let _saved = match RedirectionManager::with_redirections(redirections)
+            .and_then(|redirections| redirections.redirect_shell())
+        {
+            Ok(saved) => saved,
             Err(err) => {
-                println!("Error parsing command: {}", err);
-                return true;
+                eprintln!("{}", err);
+                return 1;
             }
         };
 
-        // Déterminer la destination de sortie
-        let stdout_redirect = command.stdout_redirect();
-        let stderr_redirect = command.stderr_redirect();
+        let branch = clause
+            .branches
+            .iter()
+            .find(|(condition, _)| self.execute_condition(condition) == 0)
+            .map(|(_, body)| body);
+        match branch.or(clause.else_body.as_ref()) {
+            Some(body) => self.execute_list(body),
+            None => 0,
+        }
+    }
+
+    /// Évalue une condition de `if`, sans que son échec ne déclenche `-e`
+    fn execute_condition(&mut self, condition: &CommandList) -> i32 {
+        self.condition_depth += 1;
+        let status = self.execute_list(condition);
+        self.condition_depth -= 1;
+        status
+    }
+
+    fn execute_simple_command(&mut self, parsed: &ParsedCommand) -> i32 {
+        let mut command = match Expander::new(self).expand_command(parsed) {
+            Ok(command) => command,
+            Err(err) => return self.expansion_failed(err),
+        };
+
+        self.trace(&command);
+        let substitutions = std::mem::take(&mut command.substitutions);
+        let status = self.execute_expanded_command(&command, parsed);
+        for pid in Self::close_substitutions(substitutions) {
+            Self::wait_child(pid);
+        }
+
+        if let Some(last) = command.words.last() {
+            self.last_argument = last.clone();
+        }
+        status
+    }
+
+    fn execute_expanded_command(&mut self, command: &ExpandedCommand, parsed: &ParsedCommand) -> i32 {
+        let Some(name) = command.name() else {
+            return match self.execute_assignments(command) {
+                0 => command.substitution_status.unwrap_or(0),
+                status => status,
+            };
+        };
 
         // Commandes internes
-        if let Some(cmd) = self.command_registry.get(&command.name) {
-            return self
-                .execute_builtin(cmd, &command.args, stdout_redirect, stderr_redirect)
-                .unwrap_or_else(|err| {
-                    println!("{}", err);
-                    true
-                });
+        let registry = Rc::clone(&self.command_registry);
+        if let Some(cmd) = registry.get(name) {
+            return self.execute_builtin(cmd, command).unwrap_or_else(|err| {
+                eprintln!("{}", self.located(describe(&err)));
+                1
+            });
         }
 
         // Commandes externes
-        if crate::utils::path::find_executable_in_path(&command.name).is_some() {
-            return self
-                .execute_external(
-                    &command.name,
-                    &command.args,
-                    stdout_redirect,
-                    stderr_redirect,
-                )
-                .unwrap_or_else(|err| {
-                    println!("Error executing {}: {}", command.name, err);
-                    true
-                });
+        let path = match self.resolve_external(name) {
+            Ok(path) => path,
+            Err(status) => return status,
+        };
+
+        let pgid = self.job_control.then_some(0);
+        match self.spawn_external(&path, command, None, None, pgid) {
+            Ok(pid) => {
+                let mut job = Job::new(parsed.to_string());
+                job.add_process(Ok(pid));
+                self.wait_foreground(job)
+            }
+            Err(status) => status,
+        }
+    }
+
+    /// Affiche la commande sur stderr, précédée de `$PS4`, avec `-x`
+    fn trace(&self, command: &ExpandedCommand) {
+        if self.xtrace {
+            let prefix = self.variables.get("PS4").unwrap_or_else(|| "+ ".to_string());
+            eprintln!("{}{}", prefix, command.trace());
         }
+    }
 
-        println!("{}: command not found", command.name);
-        true
+    /// Ferme les extrémités de pipe des substitutions de processus une fois
+    /// la commande lancée ou terminée, et retourne les PID à attendre
+    fn close_substitutions(substitutions: Vec<ProcessSubstitution>) -> Vec<Pid> {
+        substitutions.into_iter().map(ProcessSubstitution::close).collect()
     }
 
-    fn execute_builtin(
-        &self,
-        cmd: &Box<dyn crate::command::CommandHandler>,
-        args: &[String],
-        stdout_redirect: Option<&Redirection>,
-        stderr_redirect: Option<&Redirection>,
-    ) -> anyhow::Result<bool> {
-        let mut redirections = RedirectionManager::with_redirections(
-            stdout_redirect,
-            stderr_redirect,
-        )?;
+    /// Exécute un pipeline : toutes les commandes tournent en parallèle,
+    /// reliées par des pipes, puis on attend la fin de chacune. Le code de
+    /// retour est celui de la dernière commande.
+    fn execute_multi_stage_pipeline(&mut self, pipeline: &Pipeline) -> i32 {
+        let (job, substitutions) = self.launch_job(pipeline);
+        let status = self.wait_foreground(job);
 
-        cmd.execute(args, &self.command_registry, &mut redirections)
+        // Les substitutions de processus ne sont attendues qu'une fois tout
+        // le pipeline terminé : plus tôt, une étape pourrait rester bloquée
+        for pid in substitutions {
+            Self::wait_child(pid);
+        }
+        status
     }
 
-    fn execute_external(
-        &self,
-        name: &str,
-        args: &[String],
-        stdout_redirect: Option<&Redirection>,
-        stderr_redirect: Option<&Redirection>,
-    ) -> anyhow::Result<bool> {
-        let mut cmd = Command::new(name);
-        cmd.args(args);
-
-        if let Some(redir) = stdout_redirect {
-            if let Some(parent) = std::path::Path::new(&redir.target).parent() {
-                std::fs::create_dir_all(parent)?;
+    /// Lance toutes les étapes d'un pipeline dans un même groupe de
+    /// processus, sans les attendre. Retourne le job et les PID des
+    /// substitutions de processus de ses commandes.
+    fn launch_job(&mut self, pipeline: &Pipeline) -> (Job, Vec<Pid>) {
+        let last = pipeline.commands.len() - 1;
+        let mut job = Job::new(pipeline.to_string());
+        let mut substitutions = Vec::new();
+        let mut stdin: Option<File> = None;
+
+        for (index, command) in pipeline.commands.iter().enumerate() {
+            let (next_stdin, stdout) = if index < last {
+                match create_pipe() {
+                    Ok((reader, writer)) => (Some(reader), Some(writer)),
+                    Err(err) => {
+                        eprintln!("pipe: {}", strerror(&err));
+                        job.add_process(Err(1));
+                        break;
+                    }
+                }
+            } else {
+                (None, None)
+            };
+
+            // Le premier processus lancé devient leader du groupe
+            let pgid = self.job_control.then(|| job.pgid().unwrap_or(0));
+            // Les extrémités passées à l'étape sont fermées dans le shell
+            // dès qu'elle est lancée, pour que les lecteurs voient EOF
+            let stage = match command {
+                parser::Command::Simple(command) => {
+                    self.spawn_pipeline_stage(command, stdin.take(), stdout, pgid, &mut substitutions)
+                }
+                parser::Command::If(clause) => {
+                    self.fork_subshell(stdin.take(), stdout, pgid, |shell| shell.execute_if(clause))
+                }
+            };
+            job.add_process(stage);
+            stdin = next_stdin;
+        }
+
+        (job, substitutions)
+    }
+
+    /// Lance une étape de pipeline en arrière-plan et retourne son PID, ou
+    /// directement son code de retour si elle n'a pas pu être lancée.
+    ///
+    /// Les commandes internes sont exécutées dans un processus fils afin de
+    /// pouvoir écrire dans le pipe pendant que l'étape suivante le lit.
+    /// L'étape rejoint le groupe `pgid` (0 : un nouveau groupe) avec le
+    /// contrôle des jobs. Les PID des substitutions de processus de l'étape
+    /// sont ajoutés à `substitutions`.
+    fn spawn_pipeline_stage(
+        &mut self,
+        command: &ParsedCommand,
+        stdin: Option<File>,
+        stdout: Option<File>,
+        pgid: Option<Pid>,
+        substitutions: &mut Vec<Pid>,
+    ) -> Result<Pid, i32> {
+        let mut command = match Expander::new(self).expand_command(command) {
+            Ok(command) => command,
+            Err(err) => return Err(self.expansion_failed(err)),
+        };
+
+        self.trace(&command);
+        let pending = std::mem::take(&mut command.substitutions);
+        let stage = self.launch_pipeline_stage(&command, stdin, stdout, pgid);
+        substitutions.extend(Self::close_substitutions(pending));
+        stage
+    }
+
+    fn launch_pipeline_stage(
+        &mut self,
+        command: &ExpandedCommand,
+        stdin: Option<File>,
+        stdout: Option<File>,
+        pgid: Option<Pid>,
+    ) -> Result<Pid, i32> {
+        let Some(name) = command.name() else {
+            return Err(Self::apply_redirections_only(command));
+        };
+
+        let registry = Rc::clone(&self.command_registry);
+        if let Some(cmd) = registry.get(name) {
+            return self.fork_subshell(stdin, stdout, pgid, |shell| {
+                shell.execute_builtin(cmd, command).unwrap_or_else(|err| {
+                    eprintln!("{}", describe(&err));
+                    1
+                })
+            });
+        }
+
+        let path = self.resolve_external(name)?;
+        self.spawn_external(&path, command, stdin, stdout, pgid)
+    }
+
+    /// Exécute `run` dans un processus fils relié aux extrémités de pipe
+    /// reçues, et retourne son PID sans l'attendre (ou le code de retour si
+    /// le `fork` échoue). Le fils rejoint le groupe `pgid` (0 : un nouveau
+    /// groupe) avec le contrôle des jobs.
+    fn fork_subshell(
+        &mut self,
+        stdin: Option<File>,
+        stdout: Option<File>,
+        pgid: Option<Pid>,
+        run: impl FnOnce(&mut Self) -> i32,
+    ) -> Result<Pid, i32> {
+        match process::fork() {
+            Ok(Some(pid)) => {
+                // Aussi fait ici pour que le groupe existe avant que
+                // l'étape suivante ne le rejoigne
+                if let Some(pgid) = pgid {
+                    let _ = signals::set_process_group(pid, pgid);
+                }
+                Ok(pid)
+            }
+            Ok(None) => {
+                self.enter_subshell(pgid);
+                if let Err(err) = Self::attach_stdio(stdin, stdout) {
+                    eprintln!("{}", strerror(&err));
+                    process::exit_child(1);
+                }
+                let status = run(self);
+                process::exit_child(status);
             }
-            cmd.stdout(
-                OpenOptions::new()
-                    .write(true)
-                    .create(true)
-                    .append(redir.is_append())
-                    .truncate(redir.is_overwrite())
-                    .open(&redir.target)?,
-            );
-        }
-
-        if let Some(redir) = stderr_redirect {
-            if let Some(parent) = std::path::Path::new(&redir.target).parent() {
-                std::fs::create_dir_all(parent)?;
+            Err(err) => {
+                eprintln!("fork: {}", strerror(&err));
+                Err(1)
+            }
+        }
+    }
+
+    /// Branche les extrémités de pipe reçues sur stdin/stdout du processus
+    fn attach_stdio(stdin: Option<File>, stdout: Option<File>) -> io::Result<()> {
+        if let Some(stdin) = stdin {
+            process::redirect_fd(stdin.as_raw_fd(), libc::STDIN_FILENO)?;
+        }
+        if let Some(stdout) = stdout {
+            process::redirect_fd(stdout.as_raw_fd(), libc::STDOUT_FILENO)?;
+        }
+        Ok(())
+    }
+
+    fn wait_child(pid: Pid) -> i32 {
+        process::wait_for(pid).unwrap_or_else(|err| {
+            eprintln!("wait: {}", strerror(&err));
+            1
+        })
+    }
+
+    /// Ligne sans commande (ex: `A=1 > fichier`) : les variables sont
+    /// affectées dans le shell et les fichiers de redirection créés.
+    fn execute_assignments(&mut self, command: &ExpandedCommand) -> i32 {
+        for (name, value) in &command.assignments {
+            self.variables.set(name, value);
+        }
+
+        Self::apply_redirections_only(command)
+    }
+
+    /// Crée les fichiers de redirection d'une commande sans nom ; dans un
+    /// pipeline, ses affectations sont sans effet sur le shell.
+    fn apply_redirections_only(command: &ExpandedCommand) -> i32 {
+        match RedirectionManager::with_redirections(&command.redirections) {
+            Ok(_) => 0,
+            Err(err) => {
+                eprintln!("{}", err);
+                1
             }
-            cmd.stderr(
-                OpenOptions::new()
-                    .write(true)
-                    .create(true)
-                    .append(redir.is_append())
-                    .truncate(redir.is_overwrite())
-                    .open(&redir.target)?,
-            );
         }
+    }
 
-        cmd.status()?;
-        Ok(true)
+    fn execute_builtin(
+        &mut self,
+        cmd: &dyn CommandHandler,
+        command: &ExpandedCommand,
+    ) -> anyhow::Result<i32> {
+        let mut redirections = RedirectionManager::with_redirections(&command.redirections)?;
+
+        cmd.execute(command.args(), self, &mut redirections)
     }
 
-    fn enable_raw_mode(&mut self) -> io::Result<()> {
-        let fd = io::stdin().as_raw_fd();
+    /// Trouve l'exécutable correspondant à `name`, ou affiche l'erreur et
+    /// retourne le code de retour adéquat (127 introuvable, 126 non exécutable).
+    fn resolve_external(&self, name: &str) -> Result<PathBuf, i32> {
+        // Un nom contenant un `/` est un chemin : pas de recherche dans PATH
+        if !name.contains('/') {
+            return find_executable_in_path(name).ok_or_else(|| {
+                eprintln!("{}", self.located(format_args!("{}: command not found", name)));
+                process::STATUS_NOT_FOUND
+            });
+        }
+
+        let path = PathBuf::from(name);
+        let error = if !path.exists() {
+            Some(("No such file or directory", process::STATUS_NOT_FOUND))
+        } else if path.is_dir() {
+            Some(("Is a directory", process::STATUS_NOT_EXECUTABLE))
+        } else if !is_executable(&path) {
+            Some(("Permission denied", process::STATUS_NOT_EXECUTABLE))
+        } else {
+            None
+        };
 
-        // Récupérer et sauvegarder les paramètres originaux
-        let original = Termios::from_fd(fd)?;
-        self.original_termios = Some(original);
+        if let Some((message, status)) = error {
+            eprintln!("{}", self.located(format_args!("{}: {}", name, message)));
+            return Err(status);
+        }
 
-        // Créer une copie modifiée pour le mode raw
-        let mut raw = original;
-        raw.c_lflag &= !(ICANON | ECHO);
+        Ok(path)
+    }
 
-        // Appliquer les nouveaux paramètres
-        tcsetattr(fd, TCSANOW, &raw)?;
-        Ok(())
+    /// Lance une commande externe sans attendre sa fin et retourne son PID,
+    /// ou directement son code de retour si elle n'a pas pu être lancée.
+    /// Avec `pgid`, elle rejoint ce groupe de processus (0 : un nouveau).
+    fn spawn_external(
+        &self,
+        path: &Path,
+        command: &ExpandedCommand,
+        stdin: Option<File>,
+        stdout: Option<File>,
+        pgid: Option<Pid>,
+    ) -> Result<Pid, i32> {
+        let mut cmd = self
+            .build_external(path, command, stdin, stdout)
+            .map_err(|err| {
+                eprintln!("{}", err);
+                1
+            })?;
+
+        if let Some(pgid) = pgid {
+            cmd.process_group(pgid);
+        }
+        let job = pgid.is_some();
+        unsafe {
+            cmd.pre_exec(move || {
+                signals::restore_default_signals(job);
+                Ok(())
+            });
+        }
+
+        match cmd.spawn() {
+            Ok(child) => Ok(child.id() as Pid),
+            Err(err) => {
+                eprintln!("Error executing {}: {}", path.display(), strerror(&err));
+                Err(process::spawn_error_status(&err))
+            }
+        }
     }
 
-    fn disable_raw_mode(&self) -> io::Result<()> {
-        if let Some(original) = &self.original_termios {
-            let fd = io::stdin().as_raw_fd();
-            tcsetattr(fd, TCSANOW, original)?;
+    /// Prépare une commande externe ; ses redirections s'appliquent après
+    /// les pipes éventuellement fournis et ont donc priorité sur eux.
+    fn build_external(
+        &self,
+        path: &Path,
+        command: &ExpandedCommand,
+        stdin: Option<File>,
+        stdout: Option<File>,
+    ) -> anyhow::Result<Command> {
+        let mut cmd = Command::new(path);
+        cmd.arg0(command.name().unwrap_or_default());
+        cmd.args(command.args());
+        // Les affectations préfixes ne valent que pour cette commande
+        cmd.envs(command.assignments.iter().map(|(name, value)| (name, value)));
+
+        let mut redirections = RedirectionManager::new();
+        if let Some(stdin) = stdin {
+            redirections.set_file(FileDescriptor::STDIN, stdin);
         }
-        Ok(())
+        if let Some(stdout) = stdout {
+            redirections.set_file(FileDescriptor::STDOUT, stdout);
+        }
+        redirections.apply_all(&command.redirections)?;
+        redirections.configure_command(&mut cmd)?;
+
+        Ok(cmd)
     }
 
     fn handle_autocomplete(&mut self, input: &mut String) {
@@ -228,7 +1120,7 @@ impl Shell {
             return;
         }
 
-        let prefix = parts.get(0).unwrap_or(&"");
+        let prefix = parts.first().unwrap_or(&"");
 
         // Collecter les built-in commands et les commandes PATH
         let builtin_matches = self.command_registry.find_command_starting_with(prefix);
@@ -238,7 +1130,7 @@ impl Shell {
         let mut matches: Vec<String> = builtin_matches
             .into_iter()
             .map(String::from)
-            .chain(path_matches.into_iter())
+            .chain(path_matches)
             .collect();
 
         matches.sort();
@@ -300,7 +1192,7 @@ impl Shell {
             print!("{}  ", cmd);
         }
         println!();
-        print!("$ {}", current_input);
+        print!("{}{}", PROMPT, current_input);
         io::stdout().flush().unwrap();
     }
 
@@ -341,6 +1233,6 @@ impl Shell {
 
 impl Drop for Shell {
     fn drop(&mut self) {
-        let _ = self.disable_raw_mode();
+        let _ = self.terminal.restore();
     }
 }
@@ -0,0 +1,77 @@
+use std::path::{Path, PathBuf};
+
+use crate::parser::Lexer;
+use crate::expansion::Expander;
+use crate::shell::Shell;
+use crate::utils::error::strerror;
+use crate::utils::users;
+
+/// Profil commun à tous les utilisateurs, lu par les shells de connexion
+const SYSTEM_PROFILE: &str = "/etc/profile";
+/// Profil de l'utilisateur, relatif à son dossier personnel
+const USER_PROFILE: &str = ".profile";
+/// Configuration des shells interactifs, relative au dossier personnel
+const RC_FILE: &str = ".shellrc";
+
+/// Lit les fichiers de démarrage dans le shell courant, dans l'ordre :
+/// `/etc/profile` puis `~/.profile` pour un shell de connexion, puis
+/// `~/.shellrc` et le fichier désigné par `$ENV` pour un shell interactif
+/// (sauf `rc` désactivé par `--norc`).
+///
+/// Un fichier absent est ignoré ; les erreurs sont affichées avec le fichier
+/// et la ligne concernés, sans interrompre le démarrage.
+pub fn load(shell: &mut Shell, login: bool, rc: bool) {
+    if login {
+        source_if_exists(shell, Path::new(SYSTEM_PROFILE));
+        if let Some(home) = home_dir(shell) {
+            source_if_exists(shell, &home.join(USER_PROFILE));
+        }
+    }
+
+    if !(rc && shell.is_interactive()) {
+        return;
+    }
+
+    if let Some(home) = home_dir(shell) {
+        source_if_exists(shell, &home.join(RC_FILE));
+    }
+    if let Some(path) = env_file(shell) {
+        source_if_exists(shell, &path);
+    }
+}
+
+fn source_if_exists(shell: &mut Shell, path: &Path) {
+    if !path.exists() {
+        return;
+    }
+    if let Err(err) = shell.source_file(path) {
+        eprintln!("{}: {}", path.display(), strerror(&err));
+    }
+}
+
+/// Dossier personnel : `$HOME`, ou celui de `/etc/passwd`
+fn home_dir(shell: &Shell) -> Option<PathBuf> {
+    shell
+        .variables()
+        .get("HOME")
+        .filter(|home| !home.is_empty())
+        .or_else(users::current_home_dir)
+        .map(PathBuf::from)
+}
+
+/// Fichier désigné par `$ENV`, dont la valeur subit l'expansion des
+/// paramètres (`ENV=$HOME/.envrc`)
+fn env_file(shell: &mut Shell) -> Option<PathBuf> {
+    let value = shell.variables().get("ENV").filter(|value| !value.is_empty())?;
+
+    let expanded = Lexer::lex_word(&value)
+        .and_then(|word| Expander::new(shell).expand_word(&word));
+    match expanded {
+        Ok(path) if !path.is_empty() => Some(PathBuf::from(path)),
+        Ok(_) => None,
+        Err(err) => {
+            eprintln!("ENV: {}", err);
+            None
+        }
+    }
+}
@@ -0,0 +1,39 @@
+use std::io;
+
+/// Texte d'une erreur système tel que l'affiche `strerror` (`No such file or
+/// directory`), sans le ` (os error 2)` ajouté par Rust
+pub fn strerror(err: &io::Error) -> String {
+    let mut message = err.to_string();
+    if let Some(code) = err.raw_os_error() {
+        let suffix = format!(" (os error {})", code);
+        if message.ends_with(&suffix) {
+            message.truncate(message.len() - suffix.len());
+        }
+    }
+    message
+}
+
+/// Message d'une erreur quelconque, une erreur système étant affichée comme
+/// par `strerror`
+pub fn describe(err: &anyhow::Error) -> String {
+    match err.downcast_ref::<io::Error>() {
+        Some(err) => strerror(err),
+        None => err.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn os_errors_lose_their_code() {
+        let err = io::Error::from_raw_os_error(libc::ENOENT);
+        assert_eq!(strerror(&err), "No such file or directory");
+        assert_eq!(describe(&err.into()), "No such file or directory");
+
+        let err = io::Error::other("Is a directory");
+        assert_eq!(strerror(&err), "Is a directory");
+        assert_eq!(describe(&anyhow::anyhow!("x: y")), "x: y");
+    }
+}
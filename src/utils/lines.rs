@@ -0,0 +1,40 @@
+use std::io;
+use std::os::unix::io::RawFd;
+
+/// Lit des lignes sur un descripteur, un octet à la fois : rien n'est lu
+/// au-delà de la ligne retournée, et les commandes lancées entre deux
+/// lectures trouvent la suite de l'entrée intacte (`echo 'cat
+/// texte' | shell`).
+pub struct LineReader {
+    fd: RawFd,
+}
+
+impl LineReader {
+    pub fn new(fd: RawFd) -> Self {
+        Self { fd }
+    }
+
+    /// Ligne suivante sans son retour à la ligne ; `None` en fin de fichier.
+    /// Une dernière ligne non terminée est retournée telle quelle.
+    pub fn next_line(&mut self) -> io::Result<Option<String>> {
+        let mut line = Vec::new();
+
+        loop {
+            let mut byte = 0u8;
+            match unsafe { libc::read(self.fd, (&mut byte as *mut u8).cast(), 1) } {
+                0 if line.is_empty() => return Ok(None),
+                0 => break,
+                -1 => {
+                    let err = io::Error::last_os_error();
+                    if err.kind() != io::ErrorKind::Interrupted {
+                        return Err(err);
+                    }
+                }
+                _ if byte == b'\n' => break,
+                _ => line.push(byte),
+            }
+        }
+
+        Ok(Some(String::from_utf8_lossy(&line).into_owned()))
+    }
+}
@@ -1 +1,4 @@
+pub mod error;
+pub mod lines;
 pub mod path;
+pub mod users;
@@ -1,5 +1,5 @@
 use std::os::unix::fs::PermissionsExt;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 // Constante pour les bits de permission d'exécution (user, group, other)
 const PERMISSION_EXECUTE_MASK: u32 = 0o111;
@@ -8,19 +8,30 @@ pub fn find_executable_in_path(cmd_name: &str) -> Option<PathBuf> {
     if let Ok(path_var) = std::env::var("PATH") {
         for path in std::env::split_paths(&path_var) {
             let executable_path = path.join(cmd_name);
-            if executable_path.is_file() {
-                if let Ok(metadata) = executable_path.metadata() {
-                    let permissions = metadata.permissions();
-                    if permissions.mode() & PERMISSION_EXECUTE_MASK != 0 {
-                        return Some(executable_path);
-                    }
-                }
+            if executable_path.is_file() && is_executable(&executable_path) {
+                return Some(executable_path);
             }
         }
     }
     None
 }
 
+/// Premier fichier ordinaire nommé `name` dans les dossiers de PATH,
+/// exécutable ou non (fichiers lus par `source`)
+pub fn find_file_in_path(name: &str) -> Option<PathBuf> {
+    let path_var = std::env::var("PATH").ok()?;
+    std::env::split_paths(&path_var)
+        .map(|path| path.join(name))
+        .find(|candidate| candidate.is_file())
+}
+
+/// Indique si le fichier possède au moins un bit d'exécution
+pub fn is_executable(path: &Path) -> bool {
+    path.metadata()
+        .map(|metadata| metadata.permissions().mode() & PERMISSION_EXECUTE_MASK != 0)
+        .unwrap_or(false)
+}
+
 pub fn find_executables_with_prefix(prefix: &str) -> Vec<String> {
     let mut executables = Vec::new();
 
@@ -0,0 +1,27 @@
+use std::fs;
+
+const PASSWD_FILE: &str = "/etc/passwd";
+
+/// Dossier personnel de `user` d'après `/etc/passwd`
+pub fn home_dir(user: &str) -> Option<String> {
+    find_home(|name, _| name == user)
+}
+
+/// Dossier personnel de l'utilisateur qui exécute le shell
+pub fn current_home_dir() -> Option<String> {
+    let uid = unsafe { libc::getuid() }.to_string();
+    find_home(|_, entry_uid| entry_uid == uid)
+}
+
+/// Parcourt les lignes `nom:mdp:uid:gid:gecos:dossier:shell`
+fn find_home(matches: impl Fn(&str, &str) -> bool) -> Option<String> {
+    let content = fs::read_to_string(PASSWD_FILE).ok()?;
+
+    content.lines().find_map(|line| {
+        let fields: Vec<&str> = line.split(':').collect();
+        match fields[..] {
+            [name, _, uid, _, _, home, ..] if matches(name, uid) => Some(home.to_string()),
+            _ => None,
+        }
+    })
+}
@@ -0,0 +1,54 @@
+use std::collections::HashMap;
+
+/// Variables du shell.
+///
+/// Les variables exportées vivent directement dans l'environnement du
+/// processus, ce qui les transmet aux commandes lancées ; les autres restent
+/// locales au shell.
+pub struct Variables {
+    locals: HashMap<String, String>,
+}
+
+impl Variables {
+    pub fn new() -> Self {
+        Self {
+            locals: HashMap::new(),
+        }
+    }
+
+    pub fn get(&self, name: &str) -> Option<String> {
+        self.locals
+            .get(name)
+            .cloned()
+            .or_else(|| std::env::var(name).ok())
+    }
+
+    /// Affecte une variable ; une variable déjà exportée le reste
+    pub fn set(&mut self, name: &str, value: &str) {
+        if std::env::var_os(name).is_some() {
+            std::env::set_var(name, value);
+        } else {
+            self.locals.insert(name.to_string(), value.to_string());
+        }
+    }
+
+    /// Exporte une variable, avec une nouvelle valeur ou sa valeur actuelle
+    pub fn export(&mut self, name: &str, value: Option<&str>) {
+        let local = self.locals.remove(name);
+        if let Some(value) = value.map(str::to_string).or(local) {
+            std::env::set_var(name, value);
+        }
+    }
+
+    pub fn unset(&mut self, name: &str) {
+        self.locals.remove(name);
+        std::env::remove_var(name);
+    }
+
+    /// Variables exportées, triées par nom
+    pub fn exported(&self) -> Vec<(String, String)> {
+        let mut exported: Vec<(String, String)> = std::env::vars().collect();
+        exported.sort();
+        exported
+    }
+}